@@ -0,0 +1,113 @@
+use std::mem;
+
+use crate::item::Item;
+
+/// Items put away out of the list, remembering the last batch so it can be brought back at once.
+#[derive(Debug, Default)]
+pub struct Archive {
+	items: Vec<Item>,
+	/// Where each item of the last batch was in the list, in ascending order. The batch is the
+	/// tail of `items`.
+	last_batch: Vec<usize>,
+}
+
+impl Archive {
+	pub fn items(&self) -> &[Item] {
+		&self.items
+	}
+
+	pub const fn can_undo(&self) -> bool {
+		!self.last_batch.is_empty()
+	}
+
+	/// Moves every selected item out of `items` and into the archive, unselected, as one batch.
+	/// A selected recurring item starts its next cycle instead and stays, as it would when
+	/// completed. Returns how many were archived.
+	pub fn archive_selected(&mut self, items: &mut Vec<Item>) -> usize {
+		let mut batch = Vec::new();
+		let mut kept = Vec::with_capacity(items.len());
+		for (i, mut item) in items.drain(..).enumerate() {
+			if !item.selected {
+				kept.push(item);
+				continue;
+			}
+			item.selected = false;
+			if item.recurring {
+				item.complete();
+				kept.push(item);
+			} else {
+				batch.push(i);
+				self.items.push(item);
+			}
+		}
+		*items = kept;
+		let archived = batch.len();
+		if archived > 0 {
+			self.last_batch = batch;
+		}
+		archived
+	}
+
+	/// Puts the last batch back where it came from. Returns how many items were restored.
+	pub fn undo(&mut self, items: &mut Vec<Item>) -> usize {
+		let batch = mem::take(&mut self.last_batch);
+		let restored = self.items.split_off(self.items.len() - batch.len());
+		for (position, item) in batch.iter().zip(restored) {
+			items.insert((*position).min(items.len()), item);
+		}
+		batch.len()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn list(descriptions: &[&str]) -> Vec<Item> {
+		descriptions
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect()
+	}
+
+	fn descriptions(items: &[Item]) -> Vec<&str> {
+		items.iter().map(|item| item.description.as_str()).collect()
+	}
+
+	#[test]
+	fn only_the_selected_items_are_archived() {
+		let mut items = list(&["a", "b", "c", "d"]);
+		items[1].selected = true;
+		items[3].selected = true;
+		let mut archive = Archive::default();
+		assert_eq!(archive.archive_selected(&mut items), 2);
+		assert_eq!(descriptions(&items), ["a", "c"]);
+		assert_eq!(descriptions(archive.items()), ["b", "d"]);
+		assert!(archive.items().iter().all(|item| !item.selected));
+	}
+
+	#[test]
+	fn undo_puts_the_batch_back_in_place() {
+		let mut items = list(&["a", "b", "c", "d"]);
+		items[0].selected = true;
+		items[2].selected = true;
+		let mut archive = Archive::default();
+		archive.archive_selected(&mut items);
+		assert_eq!(archive.undo(&mut items), 2);
+		assert_eq!(descriptions(&items), ["a", "b", "c", "d"]);
+		assert!(archive.items().is_empty());
+		assert!(!archive.can_undo());
+	}
+
+	#[test]
+	fn a_recurring_item_starts_over_instead() {
+		let mut items = list(&["a", "b"]);
+		items[0].recurring = true;
+		items[0].selected = true;
+		let mut archive = Archive::default();
+		assert_eq!(archive.archive_selected(&mut items), 0);
+		assert_eq!(descriptions(&items), ["a", "b"]);
+		assert!(!items[0].selected);
+		assert!(!archive.can_undo());
+	}
+}
@@ -0,0 +1,35 @@
+const BADGES: [&str; 4] = ["P0", "P1", "P2", "P3"];
+
+/// The priority label for the 1-based `rank` out of `total`. `cutoffs` are the fractions of the
+/// list, from the top, where P0, P1 and P2 end; everything after the last one is P3.
+pub fn rank_to_badge(rank: usize, total: usize, cutoffs: &[f32; 3]) -> &'static str {
+	let quantile = rank.saturating_sub(1) as f32 / total.max(1) as f32;
+	let tier = cutoffs
+		.iter()
+		.position(|&cutoff| quantile < cutoff)
+		.unwrap_or(BADGES.len() - 1);
+	BADGES[tier]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CUTOFFS: [f32; 3] = [0.1, 0.3, 0.6];
+
+	#[test]
+	fn each_tier_starts_at_its_cutoff() {
+		let badges: Vec<&str> = (1..=10)
+			.map(|rank| rank_to_badge(rank, 10, &CUTOFFS))
+			.collect();
+		assert_eq!(
+			badges,
+			["P0", "P1", "P1", "P2", "P2", "P2", "P3", "P3", "P3", "P3"]
+		);
+	}
+
+	#[test]
+	fn a_single_item_is_p0() {
+		assert_eq!(rank_to_badge(1, 1, &CUTOFFS), "P0");
+	}
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+	item::Item,
+	sorter::{Choice, Sorter},
+};
+
+/// Choices already made between two items, remembered across sorts by item id so the same pair
+/// is never asked about twice. A pair that was never asked about is still answered when the
+/// choices made imply it, like A over B and B over C putting A over C.
+#[derive(Debug, Default)]
+pub struct ChoiceCache {
+	choices: HashMap<(u64, u64), Choice>,
+	/// Every item with the ones it was ranked at least as high as, and whether more than tied.
+	below: HashMap<u64, Vec<(u64, bool)>>,
+}
+
+impl ChoiceCache {
+	pub fn get(&self, left: &Item, right: &Item) -> Option<Choice> {
+		self.choices
+			.get(&(left.id, right.id))
+			.copied()
+			.or_else(|| {
+				self.choices
+					.get(&(right.id, left.id))
+					.map(|choice| choice.flipped())
+			})
+			.or_else(|| self.implied(left.id, right.id))
+	}
+
+	/// What the choices made say about `left` against `right` through other items. Choices
+	/// that contradict each other about the pair leave it to be asked.
+	fn implied(&self, left: u64, right: u64) -> Option<Choice> {
+		match (
+			reaches(&self.below, left, right),
+			reaches(&self.below, right, left),
+		) {
+			(Some(true), None) => Some(Choice::Left),
+			(None, Some(true)) => Some(Choice::Right),
+			(Some(false), Some(false)) => Some(Choice::Equal),
+			_ => None,
+		}
+	}
+
+	pub fn insert(&mut self, left: &Item, right: &Item, choice: Choice) {
+		self.choices.remove(&(right.id, left.id));
+		self.choices.insert((left.id, right.id), choice);
+		self.unlink(left.id, right.id);
+		self.unlink(right.id, left.id);
+		let (a, b) = (left.id, right.id);
+		match choice {
+			Choice::Left => self.below.entry(a).or_default().push((b, true)),
+			Choice::Right => self.below.entry(b).or_default().push((a, true)),
+			Choice::Equal => {
+				self.below.entry(a).or_default().push((b, false));
+				self.below.entry(b).or_default().push((a, false));
+			},
+		}
+	}
+
+	/// Forgets every choice involving the item, for when it has changed since.
+	pub fn invalidate(&mut self, id: u64) {
+		self.choices
+			.retain(|&(left, right), _| left != id && right != id);
+		self.below.remove(&id);
+		for links in self.below.values_mut() {
+			links.retain(|&(below, _)| below != id);
+		}
+	}
+
+	/// Drops the link from `high` down to `low`, if there is one.
+	fn unlink(&mut self, high: u64, low: u64) {
+		if let Some(links) = self.below.get_mut(&high) {
+			links.retain(|&(below, _)| below != low);
+		}
+	}
+
+	/// Answers the sorter's questions for as long as the answer is already known.
+	pub fn resolve(&self, sorter: &mut Sorter<Item>) {
+		while sorter.make_choice_with(|left, right| self.get(left, right)) {}
+	}
+}
+
+/// Whether a chain of links in `below` ranks `high` at least as high as `low`, and if so, whether
+/// one of them can be more than ties all the way.
+fn reaches(below: &HashMap<u64, Vec<(u64, bool)>>, high: u64, low: u64) -> Option<bool> {
+	// Each item is gone through once with a strict link behind it and once without, so a chain
+	// of ties only counts when no strict one turns up.
+	let mut seen = HashSet::new();
+	let mut stack = vec![(high, false)];
+	let mut found = None;
+	while let Some((id, strict)) = stack.pop() {
+		if !seen.insert((id, strict)) {
+			continue;
+		}
+		if id == low {
+			if strict {
+				return Some(true);
+			}
+			found = Some(false);
+			continue;
+		}
+		for &(next, link) in below.get(&id).into_iter().flatten() {
+			stack.push((next, strict || link));
+		}
+	}
+	found
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn items(count: usize) -> Vec<Item> {
+		(0..count)
+			.map(|i| Item::new(format!("Item {}", i)))
+			.collect()
+	}
+
+	#[test]
+	fn a_chain_of_choices_implies_the_rest() {
+		let [a, b, c] = <[Item; 3]>::try_from(items(3)).unwrap();
+		let mut cache = ChoiceCache::default();
+		cache.insert(&a, &b, Choice::Left);
+		cache.insert(&c, &b, Choice::Right);
+		assert_eq!(cache.get(&a, &c), Some(Choice::Left));
+		assert_eq!(cache.get(&c, &a), Some(Choice::Right));
+		assert_eq!(cache.get(&b, &a), Some(Choice::Right));
+	}
+
+	#[test]
+	fn ties_all_the_way_imply_equal() {
+		let [a, b, c] = <[Item; 3]>::try_from(items(3)).unwrap();
+		let mut cache = ChoiceCache::default();
+		cache.insert(&a, &b, Choice::Equal);
+		cache.insert(&b, &c, Choice::Equal);
+		assert_eq!(cache.get(&a, &c), Some(Choice::Equal));
+	}
+
+	#[test]
+	fn contradicting_choices_leave_the_pair_to_be_asked() {
+		let [a, b, c, d] = <[Item; 4]>::try_from(items(4)).unwrap();
+		let mut cache = ChoiceCache::default();
+		// A over C and C tied with B, but also B tied with D and D tied with A.
+		cache.insert(&a, &c, Choice::Left);
+		cache.insert(&c, &b, Choice::Equal);
+		cache.insert(&b, &d, Choice::Equal);
+		cache.insert(&d, &a, Choice::Equal);
+		assert_eq!(cache.get(&a, &b), None);
+	}
+
+	#[test]
+	fn a_changed_choice_replaces_the_old_one() {
+		let [a, b, c] = <[Item; 3]>::try_from(items(3)).unwrap();
+		let mut cache = ChoiceCache::default();
+		cache.insert(&a, &b, Choice::Left);
+		cache.insert(&b, &c, Choice::Left);
+		cache.insert(&b, &a, Choice::Left);
+		assert_eq!(cache.get(&a, &b), Some(Choice::Right));
+		assert_eq!(cache.get(&a, &c), None);
+
+		cache.invalidate(b.id);
+		assert_eq!(cache.get(&a, &b), None);
+	}
+
+	#[test]
+	fn nothing_known_is_asked_again() {
+		let items = items(8);
+		let rank = |item: &Item| item.description.clone();
+		let mut cache = ChoiceCache::default();
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(items.clone()).unwrap();
+		let mut asked = 0;
+		while let Some((left, right)) = sorter.state.current_pair() {
+			assert_eq!(cache.get(left, right), None, "asked about a known pair");
+			let choice = if rank(left) > rank(right) {
+				Choice::Left
+			} else {
+				Choice::Right
+			};
+			cache.insert(left, right, choice);
+			sorter.make_choice(choice).unwrap();
+			asked += 1;
+			cache.resolve(&mut sorter);
+		}
+		assert!(asked > 0);
+
+		// The same items in another order are sorted without asking anything.
+		let mut again = Sorter::new();
+		again
+			.start_sorting(items.into_iter().rev().collect())
+			.unwrap();
+		cache.resolve(&mut again);
+		assert!(again.is_done());
+	}
+
+	#[test]
+	fn an_edited_item_is_asked_about_again() {
+		let items = items(2);
+		let mut cache = ChoiceCache::default();
+		cache.insert(&items[0], &items[1], Choice::Left);
+
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(items.clone()).unwrap();
+		cache.resolve(&mut sorter);
+		assert!(sorter.is_done());
+
+		cache.invalidate(items[1].id);
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(items).unwrap();
+		cache.resolve(&mut sorter);
+		assert!(!sorter.is_done());
+	}
+}
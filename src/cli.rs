@@ -0,0 +1,99 @@
+use std::io::{self, Write};
+
+use crate::{
+	item::Item,
+	sorter::{Choice, Sorter},
+};
+
+/// Sorts the lines read from stdin, up to the first blank one, without opening a window, and
+/// prints the ranking to stdout, highest first. Items declared equal share a line, joined by
+/// ` = `.
+///
+/// With `choices`, each question takes the next character of it as its answer: `L` or `1` for
+/// the left item, `R` or `2` for the right one and `=` for a tie, with whitespace skipped.
+/// Without, each question is asked on stderr and answered with a line on stdin.
+pub fn run(choices: Option<&str>) -> io::Result<()> {
+	let mut lines = io::stdin().lines();
+	let mut items = Vec::new();
+	for line in lines.by_ref() {
+		let line = line?;
+		let description = line.trim();
+		if description.is_empty() {
+			break;
+		}
+		items.push(Item::new(description.to_owned()));
+	}
+
+	let mut sorter = Sorter::new();
+	sorter.start_sorting(items).map_err(invalid)?;
+	let mut choices = choices.map(|choices| choices.chars().filter(|c| !c.is_whitespace()));
+	let mut stderr = io::stderr();
+	while let Some((left, right)) = sorter.state.current_pair() {
+		let choice = match &mut choices {
+			Some(choices) => {
+				let answer = choices
+					.next()
+					.ok_or_else(|| invalid("ran out of choices before the sort finished"))?;
+				parse_choice(answer)
+					.ok_or_else(|| invalid(format!("{answer:?} is not a choice")))?
+			},
+			None => loop {
+				write!(
+					stderr,
+					"1) {}  2) {}  \u{2014} choose: ",
+					left.description, right.description
+				)?;
+				stderr.flush()?;
+				let line = lines
+					.next()
+					.ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))??;
+				let mut answer = line.trim().chars();
+				match (answer.next().and_then(parse_choice), answer.next()) {
+					(Some(choice), None) => break choice,
+					_ => writeln!(stderr, "Answer 1, 2 or =.")?,
+				}
+			},
+		};
+		sorter.make_choice(choice).map_err(invalid)?;
+	}
+
+	let mut stdout = io::stdout().lock();
+	for cluster in sorter.clustered_result() {
+		let descriptions: Vec<_> = cluster
+			.iter()
+			.map(|item| item.description.as_str())
+			.collect();
+		writeln!(stdout, "{}", descriptions.join(" = "))?;
+	}
+	Ok(())
+}
+
+const fn parse_choice(answer: char) -> Option<Choice> {
+	match answer.to_ascii_uppercase() {
+		'L' | '1' => Some(Choice::Left),
+		'R' | '2' => Some(Choice::Right),
+		'=' => Some(Choice::Equal),
+		_ => None,
+	}
+}
+
+fn invalid(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, error)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn choices_read_either_way_and_in_either_case() {
+		assert_eq!(parse_choice('L'), Some(Choice::Left));
+		assert_eq!(parse_choice('l'), Some(Choice::Left));
+		assert_eq!(parse_choice('1'), Some(Choice::Left));
+		assert_eq!(parse_choice('r'), Some(Choice::Right));
+		assert_eq!(parse_choice('2'), Some(Choice::Right));
+		assert_eq!(parse_choice('='), Some(Choice::Equal));
+		assert_eq!(parse_choice('x'), None);
+		assert_eq!(parse_choice('3'), None);
+	}
+}
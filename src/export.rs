@@ -0,0 +1,370 @@
+use std::{cmp::Ordering, fmt, iter};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+	item::Item,
+	replay::Replay,
+	sorter::{Choice, DecisionLog},
+};
+
+/// The file formats a ranking can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportFormat {
+	#[default]
+	Markdown,
+	Csv,
+	Json,
+}
+
+impl ExportFormat {
+	pub const ALL: [Self; 3] = [Self::Markdown, Self::Csv, Self::Json];
+
+	/// The file name suggested when asking where to export.
+	pub const fn file_name(self) -> &'static str {
+		match self {
+			Self::Markdown => "priorities.md",
+			Self::Csv => "priorities.csv",
+			Self::Json => "priorities.json",
+		}
+	}
+
+	pub fn export(self, items: &[Item]) -> String {
+		match self {
+			Self::Markdown => export_markdown(items),
+			Self::Csv => export_csv(items),
+			Self::Json => ranking_json(items),
+		}
+	}
+}
+
+impl fmt::Display for ExportFormat {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Markdown => write!(f, "Markdown"),
+			Self::Csv => write!(f, "CSV"),
+			Self::Json => write!(f, "JSON"),
+		}
+	}
+}
+
+/// The ranking as a numbered Markdown list, with descriptions escaped so they show as typed.
+pub fn export_markdown(items: &[Item]) -> String {
+	items
+		.iter()
+		.enumerate()
+		.map(|(i, item)| format!("{}. {}\n", i + 1, escape_markdown(&item.description)))
+		.collect()
+}
+
+/// Backslash-escapes whatever Markdown would read as formatting, including a start that looks
+/// like a list marker and would nest a list inside the ranking.
+fn escape_markdown(text: &str) -> String {
+	let digits = text.chars().take_while(char::is_ascii_digit).count();
+	let mut escaped = String::with_capacity(text.len());
+	for (i, c) in text.chars().enumerate() {
+		let special = match c {
+			'\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~' => true,
+			'-' | '+' => i == 0,
+			'.' | ')' => digits > 0 && i == digits,
+			_ => false,
+		};
+		if special {
+			escaped.push('\\');
+		}
+		escaped.push(c);
+	}
+	escaped
+}
+
+/// The ranking as a table with a header row, quoting descriptions that need it. Records end in
+/// CRLF, as RFC 4180 has them.
+pub fn export_csv(items: &[Item]) -> String {
+	let quoted = |field: &str| {
+		if field.contains([',', '"', '\n', '\r']) {
+			format!("\"{}\"", field.replace('"', "\"\""))
+		} else {
+			field.to_string()
+		}
+	};
+	iter::once("rank,description\r\n".to_string())
+		.chain(
+			items
+				.iter()
+				.enumerate()
+				.map(|(i, item)| format!("{},{}\r\n", i + 1, quoted(&item.description))),
+		)
+		.collect()
+}
+
+fn ranking_json(items: &[Item]) -> String {
+	let ranking = items
+		.iter()
+		.enumerate()
+		.map(|(i, item)| json!({ "rank": i + 1, "description": item.description }))
+		.collect();
+	format!("{:#}\n", serde_json::Value::Array(ranking))
+}
+
+/// The ranking with sub-items under their parent, indented by one tab per level.
+pub fn items_to_outline(items: &[Item]) -> String {
+	let mut outline = String::new();
+	write_outline(&mut outline, items, 0);
+	outline
+}
+
+fn write_outline(outline: &mut String, items: &[Item], depth: usize) {
+	for (i, item) in items.iter().enumerate() {
+		outline.push_str(&"\t".repeat(depth));
+		outline.push_str(&format!("{}. {}\n", i + 1, item.description));
+		write_outline(outline, &item.children, depth + 1);
+	}
+}
+
+/// Every question asked in a session and how it was answered, one line per choice.
+pub fn transcript(log: &DecisionLog<Item>) -> String {
+	let mut replay = Replay::new(log.clone());
+	let mut lines = String::new();
+	while let (Some((left, right)), Some(choice)) =
+		(replay.state().current_pair(), replay.next_choice())
+	{
+		lines.push_str(&format!(
+			"Q{}: {}",
+			replay.step() + 1,
+			comparison(left, right, choice)
+		));
+		if let Some(rationale) = replay.next_rationale() {
+			lines.push_str(&format!(", because: {}", rationale));
+		}
+		lines.push('\n');
+		replay.forward();
+	}
+	lines
+}
+
+/// One answered question, as the two items and what was chosen.
+pub fn comparison(left: &Item, right: &Item, choice: Choice) -> String {
+	let answer = match choice {
+		Choice::Left => format!("chose {}", left.description),
+		Choice::Right => format!("chose {}", right.description),
+		Choice::Equal => "marked them equal".to_string(),
+	};
+	format!("{} vs {} → {}", left.description, right.description, answer)
+}
+
+/// Every answered question in `history`, one numbered line each. Unlike the transcript, this
+/// covers questions from before items were dropped straight into place.
+pub fn comparison_history(history: &[(&Item, &Item, Choice)]) -> String {
+	history
+		.iter()
+		.enumerate()
+		.map(|(i, (left, right, choice))| {
+			format!("{}. {}\n", i + 1, comparison(left, right, *choice))
+		})
+		.collect()
+}
+
+/// How comparisons between an item tagged `a` and one tagged `b` went.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AbTally {
+	pub a_wins: usize,
+	pub b_wins: usize,
+	pub ties: usize,
+}
+
+/// Counts, over every choice in a session, how often each group beat the other. Comparisons
+/// within a group, or with items in neither or both groups, are left out.
+pub fn ab_tally(log: &DecisionLog<Item>, a: &str, b: &str) -> AbTally {
+	let group = |item: &Item| {
+		let tagged = |tag: &str| item.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+		match (tagged(a), tagged(b)) {
+			(true, false) => Some(true),
+			(false, true) => Some(false),
+			_ => None,
+		}
+	};
+
+	let mut tally = AbTally::default();
+	let mut replay = Replay::new(log.clone());
+	while let (Some((left, right)), Some(choice)) =
+		(replay.state().current_pair(), replay.next_choice())
+	{
+		if let (Some(left_is_a), Some(right_is_a)) = (group(left), group(right)) {
+			if left_is_a != right_is_a {
+				match choice {
+					Choice::Equal => tally.ties += 1,
+					Choice::Left if left_is_a => tally.a_wins += 1,
+					Choice::Right if right_is_a => tally.a_wins += 1,
+					Choice::Left | Choice::Right => tally.b_wins += 1,
+				}
+			}
+		}
+		replay.forward();
+	}
+	tally
+}
+
+pub fn ab_summary(log: &DecisionLog<Item>, a: &str, b: &str) -> String {
+	let tally = ab_tally(log, a, b);
+	let total = tally.a_wins + tally.b_wins + tally.ties;
+	let verdict = match tally.a_wins.cmp(&tally.b_wins) {
+		Ordering::Greater => format!("#{} comes out ahead.", a),
+		Ordering::Less => format!("#{} comes out ahead.", b),
+		Ordering::Equal => "Neither group comes out ahead.".to_string(),
+	};
+	format!(
+		"#{} won {} and #{} won {} of {} comparisons between them, with {} ties.\n{}\n",
+		a, tally.a_wins, b, tally.b_wins, total, tally.ties, verdict
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sorter::Sorter;
+
+	fn items(descriptions: &[&str]) -> Vec<Item> {
+		descriptions
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect()
+	}
+
+	#[test]
+	fn each_format_picks_its_exporter() {
+		let items = items(&["First", "Second"]);
+		assert_eq!(
+			ExportFormat::Markdown.export(&items),
+			export_markdown(&items)
+		);
+		assert_eq!(ExportFormat::Csv.export(&items), export_csv(&items));
+		assert!(ExportFormat::Json.export(&items).starts_with('['));
+	}
+
+	#[test]
+	fn csv_quotes_commas_and_doubles_quotes() {
+		let items = items(&["Plain", "Milk, eggs", "Read \"Dune\""]);
+		assert_eq!(
+			export_csv(&items),
+			"rank,description\r\n1,Plain\r\n2,\"Milk, eggs\"\r\n3,\"Read \"\"Dune\"\"\"\r\n"
+		);
+	}
+
+	#[test]
+	fn markdown_formatting_is_escaped() {
+		assert_eq!(
+			escape_markdown("Fix *all* the [bugs]"),
+			r"Fix \*all\* the \[bugs\]"
+		);
+		assert_eq!(
+			escape_markdown("snake_case `code`"),
+			r"snake\_case \`code\`"
+		);
+		assert_eq!(
+			escape_markdown("Plain text, no marks."),
+			"Plain text, no marks."
+		);
+	}
+
+	#[test]
+	fn a_start_that_looks_like_a_list_marker_is_escaped() {
+		assert_eq!(escape_markdown("- not a bullet"), r"\- not a bullet");
+		assert_eq!(escape_markdown("+1 for this"), r"\+1 for this");
+		assert_eq!(escape_markdown("2. not a number"), r"2\. not a number");
+		assert_eq!(escape_markdown("10) either"), r"10\) either");
+		assert_eq!(escape_markdown("Well-known v2.0"), "Well-known v2.0");
+	}
+
+	#[test]
+	fn markdown_numbers_each_item() {
+		let items = items(&["First", "Second"]);
+		assert_eq!(export_markdown(&items), "1. First\n2. Second\n");
+	}
+
+	/// Sorts `items` alphabetically and returns the log of it.
+	fn sorted_log(items: Vec<Item>) -> DecisionLog<Item> {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(items).unwrap();
+		while sorter.make_choice_with(|left, right| {
+			Some(if left.description < right.description {
+				Choice::Left
+			} else {
+				Choice::Right
+			})
+		}) {}
+		sorter.log().unwrap().clone()
+	}
+
+	#[test]
+	fn the_transcript_has_a_line_per_choice() {
+		let log = sorted_log(items(&["a", "b", "c", "d"]));
+		let transcript = transcript(&log);
+		assert_eq!(transcript.lines().count(), log.choices.len());
+		assert_eq!(transcript.lines().next(), Some("Q1: b vs a → chose a"));
+	}
+
+	#[test]
+	fn only_choices_between_the_groups_are_tallied() {
+		let scores = [
+			("a", 2, "A"),
+			("b", 3, "B"),
+			("c", 1, "A"),
+			("d", 2, "B"),
+			("e", 4, "A"),
+		];
+		let items: Vec<Item> = scores
+			.iter()
+			.map(|&(description, _, group)| {
+				let mut item = Item::new(description.into());
+				item.add_tag(group);
+				item
+			})
+			.collect();
+		let score = |item: &Item| {
+			scores
+				.iter()
+				.find(|(description, ..)| *description == item.description)
+				.map(|&(_, score, _)| score)
+		};
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(items).unwrap();
+		while sorter.make_choice_with(|left, right| {
+			Some(match score(left).cmp(&score(right)) {
+				Ordering::Greater => Choice::Left,
+				Ordering::Less => Choice::Right,
+				Ordering::Equal => Choice::Equal,
+			})
+		}) {}
+
+		assert_eq!(
+			ab_tally(sorter.log().unwrap(), "a", "b"),
+			AbTally {
+				a_wins: 2,
+				b_wins: 1,
+				ties: 1,
+			}
+		);
+	}
+
+	#[test]
+	fn an_outline_indents_two_levels() {
+		let mut list = items(&["Trip", "Work"]);
+		list[0].children = items(&["Book", "Pack"]);
+		assert_eq!(
+			items_to_outline(&list),
+			"1. Trip\n\t1. Book\n\t2. Pack\n2. Work\n"
+		);
+	}
+
+	#[test]
+	fn an_outline_indents_three_levels() {
+		let mut list = items(&["Trip"]);
+		list[0].children = items(&["Pack"]);
+		list[0].children[0].children = items(&["Socks", "Charger"]);
+		assert_eq!(
+			items_to_outline(&list),
+			"1. Trip\n\t1. Pack\n\t\t1. Socks\n\t\t2. Charger\n"
+		);
+	}
+}
@@ -0,0 +1,157 @@
+use std::{cmp::Ordering, fmt, time::SystemTime};
+
+use crate::item::{grapheme_count, Item};
+
+/// A number every item can be ordered by without asking anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+	Age,
+	Length,
+	SubItems,
+}
+
+impl Field {
+	pub const ALL: [Self; 3] = [Self::Age, Self::Length, Self::SubItems];
+
+	/// The item's value for this field, `None` when the item doesn't have one.
+	fn value(self, item: &Item, now: SystemTime) -> Option<f64> {
+		match self {
+			Self::Age => now
+				.duration_since(item.created)
+				.ok()
+				.map(|age| age.as_secs_f64()),
+			Self::Length => Some(grapheme_count(&item.description) as f64),
+			Self::SubItems => Some(item.children.len() as f64),
+		}
+	}
+}
+
+impl fmt::Display for Field {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Age => write!(f, "age"),
+			Self::Length => write!(f, "length"),
+			Self::SubItems => write!(f, "sub-items"),
+		}
+	}
+}
+
+/// A field and a direction, as offered in the sort dropdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldOrder {
+	pub field: Field,
+	pub ascending: bool,
+}
+
+impl FieldOrder {
+	pub fn all() -> Vec<Self> {
+		Field::ALL
+			.into_iter()
+			.flat_map(|field| {
+				[true, false]
+					.into_iter()
+					.map(move |ascending| Self { field, ascending })
+			})
+			.collect()
+	}
+}
+
+impl fmt::Display for FieldOrder {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let direction = if self.ascending { "↑" } else { "↓" };
+		write!(f, "By {} {}", self.field, direction)
+	}
+}
+
+/// Stable sort of `items` by `field`, with items lacking a value at the end either way.
+pub fn sort_by_field(items: &mut [Item], field: Field, ascending: bool, now: SystemTime) {
+	items.sort_by(|a, b| match (field.value(a, now), field.value(b, now)) {
+		(Some(a), Some(b)) if ascending => a.total_cmp(&b),
+		(Some(a), Some(b)) => b.total_cmp(&a),
+		(Some(_), None) => Ordering::Less,
+		(None, Some(_)) => Ordering::Greater,
+		(None, None) => Ordering::Equal,
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+
+	const DAY: Duration = Duration::from_secs(86_400);
+
+	fn descriptions(items: &[Item]) -> Vec<&str> {
+		items.iter().map(|item| item.description.as_str()).collect()
+	}
+
+	fn sorted(items: &[Item], field: Field, ascending: bool, now: SystemTime) -> Vec<Item> {
+		let mut items = items.to_vec();
+		sort_by_field(&mut items, field, ascending, now);
+		items
+	}
+
+	#[test]
+	fn by_age_with_undated_items_last() {
+		let now = SystemTime::now();
+		// Without a number of days, the item is created tomorrow and has no age yet.
+		let items: Vec<Item> = [
+			("new", Some(0)),
+			("old", Some(3)),
+			("future", None),
+			("middle", Some(1)),
+		]
+		.iter()
+		.map(|&(description, days)| {
+			let mut item = Item::new(description.into());
+			item.created = days.map_or(now + DAY, |days| now - DAY * days);
+			item
+		})
+		.collect();
+		assert_eq!(
+			descriptions(&sorted(&items, Field::Age, true, now)),
+			["new", "middle", "old", "future"]
+		);
+		assert_eq!(
+			descriptions(&sorted(&items, Field::Age, false, now)),
+			["old", "middle", "new", "future"]
+		);
+	}
+
+	#[test]
+	fn by_length_keeping_ties_in_order() {
+		let now = SystemTime::now();
+		let items: Vec<Item> = ["ccc", "a", "bb", "dd"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		assert_eq!(
+			descriptions(&sorted(&items, Field::Length, true, now)),
+			["a", "bb", "dd", "ccc"]
+		);
+		assert_eq!(
+			descriptions(&sorted(&items, Field::Length, false, now)),
+			["ccc", "bb", "dd", "a"]
+		);
+	}
+
+	#[test]
+	fn by_sub_items() {
+		let now = SystemTime::now();
+		let mut items: Vec<Item> = ["none", "two", "one"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		items[1].children = vec![Item::new("x".into()), Item::new("y".into())];
+		items[2].children = vec![Item::new("x".into())];
+		assert_eq!(
+			descriptions(&sorted(&items, Field::SubItems, true, now)),
+			["none", "one", "two"]
+		);
+		assert_eq!(
+			descriptions(&sorted(&items, Field::SubItems, false, now)),
+			["two", "one", "none"]
+		);
+	}
+}
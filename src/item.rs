@@ -1,27 +1,301 @@
+use std::{
+	cmp::Ordering,
+	collections::HashSet,
+	path::{Path, PathBuf},
+	sync::atomic::{AtomicU64, Ordering as AtomicOrdering},
+	time::SystemTime,
+};
+
+use chrono::NaiveDate;
 use iced::{
-	theme,
-	widget::{button, row, text, text_input},
-	Alignment, Element, Length,
+	alignment, theme,
+	widget::{button, checkbox, column, container, row, text, text_input, Column, Row},
+	Alignment, Color, Element, Length,
 };
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 
-#[derive(Debug, Clone)]
+use crate::{settings::Density, sorter::Identify};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
+	pub id: u64,
 	pub description: String,
+	/// Anything that doesn't fit in the description, shown under it when comparing.
+	#[serde(default)]
+	pub notes: String,
+	/// Taken as now when missing, like for a list written by hand.
+	#[serde(default = "SystemTime::now")]
+	pub created: SystemTime,
+	#[serde(default)]
+	pub recurring: bool,
+	#[serde(default)]
+	pub tags: Vec<String>,
+	#[serde(default)]
+	pub selected: bool,
+	/// Set once the item has been ranked, so editing it can't quietly invalidate the choices
+	/// made about it.
+	#[serde(default)]
+	pub description_locked: bool,
+	/// Sub-items, ranked among themselves once their parent has its place.
+	#[serde(default)]
+	pub children: Vec<Item>,
+	/// A picture shown alongside the description when comparing.
+	#[serde(default)]
+	pub image: Option<PathBuf>,
+	/// When the item has to be done by. It breaks ties in its favour.
+	#[serde(default)]
+	pub due: Option<NaiveDate>,
+	/// Ticked off but kept in the list. Left out of new sorts.
+	#[serde(default)]
+	pub completed: bool,
+	#[serde(skip)]
 	state: State,
+	#[serde(skip)]
+	child_input: String,
+	#[serde(skip)]
+	tag_input: String,
+	/// The due date as typed, which only sets `due` once it reads as a date.
+	#[serde(skip)]
+	due_input: String,
+	#[serde(skip)]
+	typed_rank: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum State {
+	#[default]
 	Idle,
 	Editing,
+	/// Waiting for a second press before the item is deleted.
+	ConfirmDelete,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
 	Edit,
 	DescriptionEdited(String),
+	NotesEdited(String),
+	DueEdited(String),
+	UnlockDescription,
 	FinishEdition,
+	RecurringToggled(bool),
+	ToggleSelect(bool),
+	ChildInputChanged(String),
+	AddChild,
+	RemoveChild(usize),
+	TagInputChanged(String),
+	AddTag,
+	RemoveTag(usize),
+	AttachImage,
+	RemoveImage,
+	TypedRankChanged(String),
+	MoveUp,
+	MoveDown,
+	ToggleComplete(bool),
+	Split,
 	Delete,
+	ConfirmDelete,
+	CancelDelete,
+}
+
+/// An item's picture, as a comparison shows it.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Picture<'a> {
+	None,
+	Found(&'a Path),
+	/// The file was moved or deleted since it was attached.
+	Missing,
+}
+
+/// How a row of the list is shown.
+#[derive(Debug, Clone, Copy)]
+pub struct RowOptions<'a> {
+	pub rank: usize,
+	/// The score from the last sort, from 1 at the top down in even steps per rank.
+	pub score: Option<f32>,
+	/// Show the rank at the start of the row. A field for typing it is shown regardless.
+	pub show_index: bool,
+	pub badge: Option<&'a str>,
+	pub density: Density,
+	/// Show a field for typing the rank instead of the rank itself.
+	pub typing_rank: bool,
+	pub show_count: bool,
+	/// Lay the row out from right to left, for right-to-left scripts.
+	pub right_to_left: bool,
+	/// Whether there is an item above to swap with.
+	pub can_move_up: bool,
+	/// Whether there is an item below to swap with.
+	pub can_move_down: bool,
+}
+
+/// What the start of a row shows for its rank.
+#[derive(Debug, PartialEq, Eq)]
+enum RankCell {
+	Typed,
+	Shown,
+	Hidden,
+}
+
+impl RowOptions<'_> {
+	const fn rank_cell(&self) -> RankCell {
+		if self.typing_rank {
+			RankCell::Typed
+		} else if self.show_index {
+			RankCell::Shown
+		} else {
+			RankCell::Hidden
+		}
+	}
+}
+
+/// The number of characters in `s` as a reader would count them, so accented letters and emoji
+/// count once however many bytes or code points they take.
+pub fn grapheme_count(s: &str) -> usize {
+	s.graphemes(true).count()
+}
+
+/// `s` with case and spacing evened out, so descriptions that only differ in those compare equal.
+pub fn normalized(s: &str) -> String {
+	s.split_whitespace()
+		.map(str::to_lowercase)
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// Whether `s` has nothing but whitespace, which never makes a usable description.
+pub fn is_blank(s: &str) -> bool {
+	s.trim().is_empty()
+}
+
+/// Whether the items of `items` that aren't completed are exactly the items of the `last`
+/// ranking, in the same order.
+pub fn is_already_ranked(items: &[Item], last: &[Item]) -> bool {
+	items
+		.iter()
+		.filter(|item| !item.completed)
+		.map(|item| item.id)
+		.eq(last.iter().map(|item| item.id))
+}
+
+/// Whether the items of `items` that aren't completed hold every item of the `last` ranking in
+/// the same order, with more added among them since.
+pub fn has_new_items(items: &[Item], last: &[Item]) -> bool {
+	let ids: HashSet<u64> = last.iter().map(|item| item.id).collect();
+	let open: Vec<&Item> = items.iter().filter(|item| !item.completed).collect();
+	open.len() > last.len()
+		&& open
+			.iter()
+			.filter(|item| ids.contains(&item.id))
+			.map(|item| item.id)
+			.eq(last.iter().map(|item| item.id))
+}
+
+/// Adds `tag` to every selected item that doesn't have it yet.
+pub fn tag_selected(items: &mut [Item], tag: &str) {
+	for item in items.iter_mut().filter(|item| item.selected) {
+		item.add_tag(tag);
+	}
+}
+
+/// Removes every selected item in one pass, so no index points at a shifted item, and returns
+/// how many were removed.
+pub fn delete_selected(items: &mut Vec<Item>) -> usize {
+	let before = items.len();
+	items.retain(|item| !item.selected);
+	before - items.len()
+}
+
+/// Moves the item at `from` so it ends up at `to`, or at the end when `to` is past it. Returns
+/// whether anything moved.
+pub fn move_item(list: &mut Vec<Item>, from: usize, to: usize) -> bool {
+	if from >= list.len() || from == to {
+		return false;
+	}
+	let item = list.remove(from);
+	let to = to.min(list.len());
+	list.insert(to, item);
+	to != from
+}
+
+/// Puts the `ranked` items back into `list` in the places of the items with the same ids, in
+/// their new order, and leaves every other item where it was. There are more ranked items than
+/// places when some were added during the sort, and those left over go after the last place.
+pub fn splice_ranked(list: &mut Vec<Item>, ranked: Vec<Item>) {
+	let ids: HashSet<u64> = ranked.iter().map(|item| item.id).collect();
+	let places: Vec<usize> = list
+		.iter()
+		.enumerate()
+		.filter(|(_, item)| ids.contains(&item.id))
+		.map(|(i, _)| i)
+		.collect();
+	let mut ranked = ranked.into_iter();
+	for (&place, item) in places.iter().zip(ranked.by_ref()) {
+		list[place] = item;
+	}
+	let end = places.last().map_or(list.len(), |&place| place + 1);
+	list.splice(end..end, ranked);
+}
+
+/// The list of sub-items reached by following `path` down from `items`, one index per level.
+/// An empty path is `items` itself.
+pub fn list_at<'a>(items: &'a [Item], path: &[usize]) -> Option<&'a [Item]> {
+	match path.split_first() {
+		None => Some(items),
+		Some((&i, rest)) => list_at(&items.get(i)?.children, rest),
+	}
+}
+
+pub fn list_at_mut<'a>(items: &'a mut Vec<Item>, path: &[usize]) -> Option<&'a mut Vec<Item>> {
+	match path.split_first() {
+		None => Some(items),
+		Some((&i, rest)) => list_at_mut(&mut items.get_mut(i)?.children, rest),
+	}
+}
+
+/// Finishes every edit in progress in `items`, so a copy taken for sorting matches what is on
+/// screen. Returns `false` if an edit can't be finished because its description is blank.
+pub fn commit_edits(items: &mut [Item]) -> bool {
+	// A pending delete is dropped along with the edit it came from.
+	for item in items
+		.iter_mut()
+		.filter(|item| !matches!(item.state, State::Idle))
+	{
+		item.update(Message::FinishEdition);
+	}
+	items.iter().all(|item| matches!(item.state, State::Idle))
+}
+
+fn next_id() -> u64 {
+	NEXT_ID.fetch_add(1, AtomicOrdering::Relaxed)
+}
+
+/// Makes sure items created from now on never reuse an id found in `items`, for items that
+/// were loaded rather than created here.
+pub fn claim_ids(items: &[Item]) {
+	for item in items {
+		NEXT_ID.fetch_max(item.id + 1, AtomicOrdering::Relaxed);
+		claim_ids(&item.children);
+	}
+}
+
+/// Gives `items` and their sub-items new ids, for items joining a list that may already use
+/// theirs.
+pub fn renew_ids(items: &mut [Item]) {
+	for item in items {
+		item.id = next_id();
+		renew_ids(&mut item.children);
+	}
+}
+
+impl Identify for Item {
+	fn id(&self) -> u64 {
+		self.id
+	}
 }
 
 impl Item {
@@ -31,59 +305,832 @@ impl Item {
 
 	pub fn new(description: String) -> Self {
 		Item {
+			id: next_id(),
 			description,
+			notes: String::new(),
+			created: SystemTime::now(),
+			recurring: false,
+			tags: Vec::new(),
+			selected: false,
+			description_locked: false,
+			children: Vec::new(),
+			image: None,
+			due: None,
+			completed: false,
 			state: State::Idle,
+			child_input: String::new(),
+			tag_input: String::new(),
+			due_input: String::new(),
+			typed_rank: String::new(),
+		}
+	}
+
+	/// The rank typed next to the item when ranking by hand.
+	pub fn typed_rank(&self) -> &str {
+		&self.typed_rank
+	}
+
+	pub fn clear_typed_rank(&mut self) {
+		self.typed_rank.clear();
+	}
+
+	fn due_input_is_valid(&self) -> bool {
+		is_blank(&self.due_input)
+			|| NaiveDate::parse_from_str(self.due_input.trim(), DATE_FORMAT).is_ok()
+	}
+
+	pub fn is_editing(&self) -> bool {
+		matches!(self.state, State::Editing)
+	}
+
+	/// The description cut down to `max_chars` characters, for places with little room.
+	pub fn summary(&self, max_chars: usize) -> String {
+		if self.description.chars().count() <= max_chars {
+			return self.description.clone();
+		}
+		let cut: String = self
+			.description
+			.chars()
+			.take(max_chars.saturating_sub(1))
+			.collect();
+		format!("{}…", cut.trim_end())
+	}
+
+	/// Everything worth knowing about the item, shown when hovering over a summary.
+	pub fn details(&self) -> String {
+		if is_blank(&self.notes) {
+			self.description.clone()
+		} else {
+			format!("{}\n\n{}", self.description, self.notes.trim())
+		}
+	}
+
+	/// Turns each non-blank line of `text` into its own item, keeping everything else about this
+	/// one. Sub-items go with the first part.
+	pub fn split(&self, text: &str) -> Vec<Item> {
+		text.lines()
+			.filter(|line| !is_blank(line))
+			.map(str::trim)
+			.enumerate()
+			.map(|(i, line)| Item {
+				id: next_id(),
+				description: line.to_string(),
+				description_locked: false,
+				children: if i == 0 {
+					self.children.clone()
+				} else {
+					Vec::new()
+				},
+				state: State::Idle,
+				..self.clone()
+			})
+			.collect()
+	}
+
+	pub fn picture(&self) -> Picture {
+		match &self.image {
+			Some(path) if path.exists() => Picture::Found(path),
+			Some(_) => Picture::Missing,
+			None => Picture::None,
+		}
+	}
+
+	/// Which of the two is due first. An item with a due date comes before one without.
+	pub fn due_first(&self, other: &Self) -> Ordering {
+		match (self.due, other.due) {
+			(Some(due), Some(other_due)) => due.cmp(&other_due),
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(None, None) => Ordering::Equal,
+		}
+	}
+
+	pub fn has_tag(&self, tag: &str) -> bool {
+		self.tags.iter().any(|existing| existing == tag)
+	}
+
+	pub fn add_tag(&mut self, tag: &str) {
+		let tag = tag.trim();
+		if !is_blank(tag) && !self.has_tag(tag) {
+			self.tags.push(tag.to_string());
+		}
+	}
+
+	/// Marks the item as done. A recurring item starts its next cycle instead and stays open,
+	/// which is what the return value says.
+	pub fn complete(&mut self) -> bool {
+		if self.recurring {
+			self.created = SystemTime::now();
+		} else {
+			self.completed = true;
 		}
+		self.recurring
 	}
 
 	pub fn update(&mut self, message: Message) {
 		match message {
 			Message::Edit => {
+				self.due_input = self.due.map(|due| due.to_string()).unwrap_or_default();
 				self.state = State::Editing;
 			},
 			Message::DescriptionEdited(new_description) => {
-				self.description = new_description;
+				if !self.description_locked {
+					self.description = new_description;
+				}
+			},
+			Message::NotesEdited(notes) => {
+				self.notes = notes;
+			},
+			// A date that doesn't read yet leaves the last good one in place, so typing one out
+			// never loses it halfway.
+			Message::DueEdited(value) => {
+				if is_blank(&value) {
+					self.due = None;
+				} else if let Ok(due) = NaiveDate::parse_from_str(value.trim(), DATE_FORMAT) {
+					self.due = Some(due);
+				}
+				self.due_input = value;
+			},
+			Message::UnlockDescription => {
+				self.description_locked = false;
 			},
 			Message::FinishEdition => {
-				if !self.description.is_empty() {
+				if !is_blank(&self.description) {
+					self.description = self.description.trim().to_string();
 					self.state = State::Idle;
 				}
 			},
-			Message::Delete => {},
+			Message::RecurringToggled(recurring) => {
+				self.recurring = recurring;
+			},
+			Message::ToggleSelect(selected) => {
+				self.selected = selected;
+			},
+			Message::ChildInputChanged(value) => {
+				self.child_input = value;
+			},
+			Message::AddChild => {
+				if !is_blank(&self.child_input) {
+					let description = self.child_input.trim().to_string();
+					self.children.push(Item::new(description));
+					self.child_input.clear();
+				}
+			},
+			Message::RemoveChild(i) => {
+				if i < self.children.len() {
+					self.children.remove(i);
+				}
+			},
+			Message::TagInputChanged(value) => {
+				self.tag_input = value;
+			},
+			Message::AddTag => {
+				let tag = self.tag_input.trim().trim_start_matches('#').to_string();
+				self.add_tag(&tag);
+				self.tag_input.clear();
+			},
+			Message::RemoveTag(i) => {
+				if i < self.tags.len() {
+					self.tags.remove(i);
+				}
+			},
+			Message::RemoveImage => {
+				self.image = None;
+			},
+			Message::TypedRankChanged(value) => {
+				self.typed_rank = value;
+			},
+			Message::AttachImage
+			| Message::MoveUp
+			| Message::MoveDown
+			| Message::ToggleComplete(_)
+			| Message::Split
+			| Message::ConfirmDelete => {},
+			Message::Delete => {
+				self.state = State::ConfirmDelete;
+			},
+			Message::CancelDelete => {
+				self.state = State::Editing;
+			},
 		}
 	}
 
-	pub fn view(&self, i: usize) -> Element<Message> {
-		match &self.state {
-			State::Idle => row![
-				text((i + 1).to_string()),
-				text(self.description.as_str()).width(Length::Fill),
-				button("Edit")
-					.on_press(Message::Edit)
-					.padding(10)
-					.style(theme::Button::Text),
-			]
-			.spacing(20)
-			.align_items(Alignment::Center)
-			.into(),
+	pub fn view(&self, i: usize, options: RowOptions) -> Element<Message> {
+		let RowOptions {
+			rank,
+			score,
+			badge,
+			density,
+			show_count,
+			right_to_left,
+			can_move_up,
+			can_move_down,
+			..
+		} = options;
+		let rank: Option<Element<Message>> = match options.rank_cell() {
+			RankCell::Typed => Some(
+				text_input(&rank.to_string(), &self.typed_rank)
+					.on_input(Message::TypedRankChanged)
+					.width(50)
+					.into(),
+			),
+			RankCell::Shown => Some(text(rank.to_string()).into()),
+			RankCell::Hidden => None,
+		};
+
+		let item: Element<Message> = match &self.state {
+			State::Idle => {
+				let mut cells: Vec<Element<Message>> =
+					vec![
+						checkbox("", self.selected, Message::ToggleSelect).into(),
+						text(badge.unwrap_or_default())
+							.style(Color::from([0.8, 0.3, 0.3]))
+							.into(),
+						// Text can't be struck through, so a completed item is dimmed instead.
+						text(self.description.as_str())
+							.style(if self.completed {
+								theme::Text::Color(Color::from([0.5, 0.5, 0.5]))
+							} else {
+								theme::Text::Default
+							})
+							.width(Length::Fill)
+							.horizontal_alignment(if right_to_left {
+								alignment::Horizontal::Right
+							} else {
+								alignment::Horizontal::Left
+							})
+							.into(),
+						text(score.map_or_else(String::new, |score| {
+							format!("score {:.0}", score * 100.0)
+						}))
+						.style(Color::from([0.5, 0.5, 0.5]))
+						.into(),
+						text(if show_count {
+							format!("({} chars)", grapheme_count(&self.description))
+						} else {
+							String::new()
+						})
+						.style(Color::from([0.5, 0.5, 0.5]))
+						.into(),
+						text(
+							self.tags
+								.iter()
+								.map(|tag| format!("#{}", tag))
+								.collect::<Vec<_>>()
+								.join(" "),
+						)
+						.style(Color::from([0.5, 0.5, 0.5]))
+						.into(),
+						text(
+							self.due
+								.map_or_else(String::new, |due| format!("due {}", due)),
+						)
+						.style(Color::from([0.5, 0.5, 0.5]))
+						.into(),
+						text(if self.recurring { "↻" } else { "" }).into(),
+						// Rows are dragged by anywhere that isn't a button, this just shows it.
+						text("⠿").style(Color::from([0.5, 0.5, 0.5])).into(),
+						move_button("↑", can_move_up.then_some(Message::MoveUp), density),
+						move_button("↓", can_move_down.then_some(Message::MoveDown), density),
+						checkbox("Done", self.completed, Message::ToggleComplete).into(),
+						button("Edit")
+							.on_press(Message::Edit)
+							.padding(density.padding())
+							.style(theme::Button::Text)
+							.into(),
+					];
+				if let Some(rank) = rank {
+					cells.insert(1, rank);
+				}
+				if right_to_left {
+					cells.reverse();
+				}
+
+				Row::with_children(cells)
+					.spacing(density.spacing())
+					.align_items(Alignment::Center)
+					.into()
+			},
 			State::Editing => {
-				let text_input = text_input("An item to prioritize...", &self.description)
-					.id(Self::text_input_id(&i))
-					.on_input(Message::DescriptionEdited)
-					.on_submit(Message::FinishEdition)
-					.padding(10);
-
-				row![
-					text_input,
-					button("Delete")
-						.on_press(Message::Delete)
-						.padding(10)
-						.style(theme::Button::Destructive)
+				let mut description_input =
+					text_input("An item to prioritize...", &self.description)
+						.id(Self::text_input_id(&i))
+						.padding(density.padding());
+				if !self.description_locked {
+					description_input = description_input
+						.on_input(Message::DescriptionEdited)
+						.on_submit(Message::FinishEdition);
+				}
+				let lock_hint = if self.description_locked {
+					row![
+						text("Locked because it has been ranked")
+							.style(Color::from([0.5, 0.5, 0.5]))
+							.width(Length::Fill),
+						button("Unlock")
+							.on_press(Message::UnlockDescription)
+							.style(theme::Button::Secondary),
+						button("Done").on_press(Message::FinishEdition),
+					]
+					.spacing(density.spacing())
+					.align_items(Alignment::Center)
+				} else {
+					row![]
+				};
+
+				let removable = self.children.iter().enumerate().fold(
+					Column::new().spacing(5),
+					|column, (i, child)| {
+						column.push(
+							row![
+								text(child.description.as_str()).width(Length::Fill),
+								button("Remove")
+									.on_press(Message::RemoveChild(i))
+									.style(theme::Button::Text),
+							]
+							.align_items(Alignment::Center),
+						)
+					},
+				);
+
+				column![
+					row![
+						description_input,
+						checkbox("Recurring", self.recurring, Message::RecurringToggled),
+						button("Split")
+							.on_press(Message::Split)
+							.padding(density.padding())
+							.style(theme::Button::Secondary),
+						button("Delete")
+							.on_press(Message::Delete)
+							.padding(density.padding())
+							.style(theme::Button::Destructive)
+					]
+					.spacing(density.spacing())
+					.align_items(Alignment::Center),
+					lock_hint,
+					self.tags
+						.iter()
+						.enumerate()
+						.fold(Row::new().spacing(5), |tags, (i, tag)| {
+							tags.push(
+								button(text(format!("#{} ×", tag)))
+									.on_press(Message::RemoveTag(i))
+									.padding(density.padding())
+									.style(theme::Button::Secondary),
+							)
+						})
+						.push(
+							text_input("Add a tag...", &self.tag_input)
+								.on_input(Message::TagInputChanged)
+								.on_submit(Message::AddTag)
+								.padding(density.padding())
+								.width(150),
+						)
+						.align_items(Alignment::Center),
+					// Notes stay editable on a locked item, they don't change what was ranked.
+					text_input("Notes...", &self.notes)
+						.on_input(Message::NotesEdited)
+						.on_submit(Message::FinishEdition)
+						.padding(density.padding()),
+					row![
+						text_input("Due date (YYYY-MM-DD)", &self.due_input)
+							.on_input(Message::DueEdited)
+							.on_submit(Message::FinishEdition)
+							.padding(density.padding())
+							.width(200),
+						text(if self.due_input_is_valid() {
+							""
+						} else {
+							"Not a date, keeping the last one"
+						})
+						.style(Color::from([0.8, 0.3, 0.3])),
+					]
+					.spacing(density.spacing())
+					.align_items(Alignment::Center),
+					match &self.image {
+						Some(path) => row![
+							text(path.display()).width(Length::Fill),
+							button("Remove Image")
+								.on_press(Message::RemoveImage)
+								.style(theme::Button::Text),
+						]
+						.align_items(Alignment::Center),
+						None => row![button("Attach Image")
+							.on_press(Message::AttachImage)
+							.style(theme::Button::Secondary)],
+					},
+					container(
+						column![
+							removable,
+							text_input("Add a sub-item...", &self.child_input)
+								.on_input(Message::ChildInputChanged)
+								.on_submit(Message::AddChild)
+								.padding(5),
+						]
+						.spacing(5)
+					)
+					.padding([0, 0, 0, 40]),
 				]
-				.spacing(20)
-				.align_items(Alignment::Center)
+				.spacing(10)
 				.into()
 			},
+			State::ConfirmDelete => row![
+				text(format!("Delete \"{}\"?", self.summary(40))).width(Length::Fill),
+				button("Confirm")
+					.on_press(Message::ConfirmDelete)
+					.padding(density.padding())
+					.style(theme::Button::Destructive),
+				button("Cancel")
+					.on_press(Message::CancelDelete)
+					.padding(density.padding())
+					.style(theme::Button::Secondary),
+			]
+			.spacing(density.spacing())
+			.align_items(Alignment::Center)
+			.into(),
+		};
+
+		if self.is_editing() || self.children.is_empty() {
+			return item;
+		}
+		column![item, children_view(&self.children)]
+			.spacing(5)
+			.into()
+	}
+}
+
+/// Sub-items in their current order, indented a level deeper than their parent.
+fn children_view(children: &[Item]) -> Element<Message> {
+	let list = children
+		.iter()
+		.enumerate()
+		.fold(Column::new().spacing(5), |column, (i, child)| {
+			let line = text(format!("{}. {}", i + 1, child.description));
+			if child.children.is_empty() {
+				column.push(line)
+			} else {
+				column.push(line).push(children_view(&child.children))
+			}
+		});
+	container(list).padding([0, 0, 0, 40]).into()
+}
+
+/// A small arrow button for moving an item by hand, disabled when there is nowhere to go.
+fn move_button<'a>(
+	label: &'a str,
+	message: Option<Message>,
+	density: Density,
+) -> Element<'a, Message> {
+	let mut move_button = button(text(label))
+		.padding(density.padding())
+		.style(theme::Button::Text);
+	if let Some(message) = message {
+		move_button = move_button.on_press(message);
+	}
+	move_button.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sorter::{Choice, Sorter};
+
+	#[test]
+	fn items_with_the_same_description_are_both_kept() {
+		let items = ["Call mum", "Pay rent", "Call mum"]
+			.into_iter()
+			.map(|description| Item::new(description.into()))
+			.collect();
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(items).unwrap();
+		while let Some((left, right)) = sorter.state.current_pair() {
+			let choice = if left.description > right.description {
+				Choice::Left
+			} else {
+				Choice::Right
+			};
+			sorter.make_choice(choice).unwrap();
+		}
+		let mut ranked = Vec::new();
+		sorter.finish_sorting(&mut ranked);
+		let descriptions: Vec<_> = ranked
+			.iter()
+			.map(|item| item.description.as_str())
+			.collect();
+		assert_eq!(descriptions, ["Pay rent", "Call mum", "Call mum"]);
+		assert_ne!(ranked[1].id, ranked[2].id);
+	}
+
+	#[test]
+	fn a_locked_description_ignores_edits() {
+		let mut item = Item::new("Write report".into());
+		item.description_locked = true;
+		item.update(Message::DescriptionEdited("Something else".into()));
+		assert_eq!(item.description, "Write report");
+
+		item.update(Message::UnlockDescription);
+		item.update(Message::DescriptionEdited("Something else".into()));
+		assert_eq!(item.description, "Something else");
+	}
+
+	#[test]
+	fn a_list_survives_a_round_trip() {
+		let mut first = Item::new("Write report".into());
+		first.add_tag("work");
+		first.notes = "Due Friday".into();
+		first.update(Message::Edit);
+		let mut second = Item::new("Call mum".into());
+		second.children.push(Item::new("Find number".into()));
+		let items = vec![first, second];
+
+		let json = serde_json::to_string(&items).unwrap();
+		let loaded: Vec<Item> = serde_json::from_str(&json).unwrap();
+		assert_eq!(loaded.len(), 2);
+		for (loaded, item) in loaded.iter().zip(&items) {
+			assert_eq!(loaded.id, item.id);
+			assert_eq!(loaded.description, item.description);
+			assert_eq!(loaded.notes, item.notes);
+			assert_eq!(loaded.tags, item.tags);
+			assert_eq!(loaded.children.len(), item.children.len());
+		}
+		// Being edited isn't saved.
+		assert!(!loaded[0].is_editing());
+	}
+
+	fn descriptions(items: &[Item]) -> Vec<&str> {
+		items.iter().map(|item| item.description.as_str()).collect()
+	}
+
+	fn list(descriptions: &[&str]) -> Vec<Item> {
+		descriptions
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect()
+	}
+
+	#[test]
+	fn completed_items_dont_count_against_the_ranking() {
+		let mut items = list(&["a", "b", "c"]);
+		let ranking = vec![items[0].clone(), items[2].clone()];
+		assert!(!is_already_ranked(&items, &ranking));
+		items[1].completed = true;
+		assert!(is_already_ranked(&items, &ranking));
+		assert!(!has_new_items(&items, &ranking));
+	}
+
+	#[test]
+	fn new_items_are_found_among_the_ranking() {
+		let mut items = list(&["a", "b", "c", "d"]);
+		items[3].completed = true;
+		let ranking = vec![items[0].clone(), items[2].clone()];
+		assert!(has_new_items(&items, &ranking));
+		// Out of the ranking's order, the list was rearranged since.
+		let reversed = vec![items[2].clone(), items[0].clone()];
+		assert!(!has_new_items(&items, &reversed));
+	}
+
+	#[test]
+	fn a_ranked_subset_goes_back_into_its_places() {
+		let mut items = list(&["a", "x", "b", "y", "c"]);
+		let ranked = vec![items[4].clone(), items[0].clone(), items[2].clone()];
+		splice_ranked(&mut items, ranked);
+		assert_eq!(descriptions(&items), ["c", "x", "a", "y", "b"]);
+	}
+
+	#[test]
+	fn ranked_items_added_during_the_sort_go_after_the_last_place() {
+		let mut items = list(&["a", "x", "b", "y"]);
+		let added = Item::new("new".into());
+		let ranked = vec![items[2].clone(), added, items[0].clone()];
+		splice_ranked(&mut items, ranked);
+		assert_eq!(descriptions(&items), ["b", "x", "new", "a", "y"]);
+	}
+
+	#[test]
+	fn moving_an_item_shifts_the_ones_between() {
+		let mut items = list(&["a", "b", "c", "d"]);
+		assert!(move_item(&mut items, 0, 2));
+		assert_eq!(descriptions(&items), ["b", "c", "a", "d"]);
+		assert!(move_item(&mut items, 3, 0));
+		assert_eq!(descriptions(&items), ["d", "b", "c", "a"]);
+	}
+
+	#[test]
+	fn moving_past_the_end_puts_the_item_last() {
+		let mut items = list(&["a", "b", "c"]);
+		assert!(move_item(&mut items, 0, 10));
+		assert_eq!(descriptions(&items), ["b", "c", "a"]);
+		assert!(!move_item(&mut items, 2, 10));
+		assert!(!move_item(&mut items, 1, 1));
+		assert!(!move_item(&mut items, 5, 0));
+	}
+
+	#[test]
+	fn splitting_replaces_the_item_with_one_per_line() {
+		let mut items = list(&["first", "both", "last"]);
+		items[1].add_tag("home");
+		let parts = items[1].split("one\n  \ntwo\n");
+		items.splice(1..=1, parts);
+		assert_eq!(descriptions(&items), ["first", "one", "two", "last"]);
+		assert!(items[1].has_tag("home") && items[2].has_tag("home"));
+		assert_ne!(items[1].id, items[2].id);
+	}
+
+	#[test]
+	fn completing_a_recurring_item_keeps_it_open() {
+		let mut item = Item::new("Water plants".into());
+		item.recurring = true;
+		item.created = SystemTime::UNIX_EPOCH;
+		assert!(item.complete());
+		assert!(!item.completed);
+		assert!(item.created > SystemTime::UNIX_EPOCH);
+
+		let loaded: Item = serde_json::from_str(&serde_json::to_string(&item).unwrap()).unwrap();
+		assert!(loaded.recurring);
+	}
+
+	#[test]
+	fn completing_a_one_off_item_closes_it() {
+		let mut item = Item::new("Renew passport".into());
+		assert!(!item.complete());
+		assert!(item.completed);
+	}
+
+	#[test]
+	fn a_tag_goes_on_exactly_the_selected_items_once() {
+		let mut items = list(&["a", "b", "c"]);
+		items[0].selected = true;
+		items[2].selected = true;
+		items[2].add_tag("home");
+		tag_selected(&mut items, "home");
+		assert_eq!(items[0].tags, ["home"]);
+		assert!(items[1].tags.is_empty());
+		assert_eq!(items[2].tags, ["home"]);
+	}
+
+	#[test]
+	fn whitespace_only_is_blank() {
+		assert!(is_blank(""));
+		assert!(is_blank(" \t "));
+		assert!(!is_blank(" a "));
+	}
+
+	#[test]
+	fn an_edit_cant_finish_with_a_blank_description() {
+		let mut item = Item::new("Pack".into());
+		item.update(Message::Edit);
+		item.update(Message::DescriptionEdited("   ".into()));
+		item.update(Message::FinishEdition);
+		assert!(item.is_editing());
+
+		item.update(Message::DescriptionEdited(" Pack bags ".into()));
+		item.update(Message::FinishEdition);
+		assert!(!item.is_editing());
+		assert_eq!(item.description, "Pack bags");
+	}
+
+	#[test]
+	fn a_list_in_the_last_order_is_already_ranked() {
+		let items = list(&["a", "b", "c"]);
+		assert!(is_already_ranked(&items, &items.clone()));
+
+		let mut permuted = items.clone();
+		permuted.swap(0, 2);
+		assert!(!is_already_ranked(&permuted, &items));
+		assert!(!is_already_ranked(&items[..2], &items));
+	}
+
+	#[test]
+	fn sub_items_are_sorted_within_their_sorted_parents() {
+		use crate::sorter::{Choice, Sorter};
+
+		// Alphabetical order, as if asked each time.
+		fn sort(list: &mut Vec<Item>) {
+			let mut sorter = Sorter::new();
+			sorter.start_sorting(list.clone()).unwrap();
+			while sorter.make_choice_with(|left, right| {
+				Some(if left.description < right.description {
+					Choice::Left
+				} else {
+					Choice::Right
+				})
+			}) {}
+			let mut ranked = Vec::new();
+			sorter.finish_sorting(&mut ranked);
+			splice_ranked(list, ranked);
 		}
+
+		let mut items = list(&["b", "a"]);
+		items[0].children = list(&["b2", "b1"]);
+		items[1].children = list(&["a3", "a1", "a2"]);
+		items[1].children[2].children = list(&["y", "x"]);
+
+		// Parents first, then each list of sub-items by its path.
+		sort(&mut items);
+		for path in [&[0][..], &[1], &[0, 1]] {
+			sort(list_at_mut(&mut items, path).unwrap());
+		}
+		assert_eq!(descriptions(&items), ["a", "b"]);
+		assert_eq!(
+			descriptions(list_at(&items, &[0]).unwrap()),
+			["a1", "a2", "a3"]
+		);
+		assert_eq!(descriptions(list_at(&items, &[1]).unwrap()), ["b1", "b2"]);
+		assert_eq!(descriptions(list_at(&items, &[0, 1]).unwrap()), ["x", "y"]);
+	}
+
+	#[test]
+	fn graphemes_are_counted_rather_than_bytes() {
+		assert_eq!(grapheme_count(""), 0);
+		assert_eq!(grapheme_count("abc"), 3);
+		assert_eq!(grapheme_count("café"), 4);
+		assert_eq!(grapheme_count("cafe\u{301}"), 4);
+		assert_eq!(grapheme_count("日本語"), 3);
+		assert_eq!(grapheme_count("👍🏽 ok"), 4);
+		assert_eq!(grapheme_count("👨‍👩‍👧"), 1);
+	}
+
+	#[test]
+	fn deleting_the_selection_keeps_the_rest_in_order() {
+		let mut items = list(&["a", "b", "c", "d", "e"]);
+		for i in [1, 2, 4] {
+			items[i].selected = true;
+		}
+		assert_eq!(delete_selected(&mut items), 3);
+		assert_eq!(descriptions(&items), ["a", "d"]);
+		assert_eq!(delete_selected(&mut items), 0);
+	}
+
+	#[test]
+	fn the_tooltip_has_the_full_text_and_the_notes() {
+		let mut item = Item::new("Plan the weekend trip to the coast".into());
+		assert_eq!(item.summary(12), "Plan the we…");
+		assert_eq!(item.summary(100), item.description);
+		assert_eq!(item.details(), item.description);
+
+		item.notes = " Check the tides \n".into();
+		assert_eq!(
+			item.details(),
+			"Plan the weekend trip to the coast\n\nCheck the tides"
+		);
+	}
+
+	#[test]
+	fn a_picture_is_shown_only_while_its_file_exists() {
+		let mut item = Item::new("Repaint the fence".into());
+		assert_eq!(item.picture(), Picture::None);
+
+		let existing = std::env::current_exe().unwrap();
+		item.image = Some(existing.clone());
+		assert_eq!(item.picture(), Picture::Found(&existing));
+
+		item.image = Some(existing.with_extension("missing.png"));
+		assert_eq!(item.picture(), Picture::Missing);
+	}
+
+	#[test]
+	fn starting_a_sort_finishes_edits_in_progress() {
+		let mut items = list(&["a", "b"]);
+		items[0].update(Message::Edit);
+		items[0].update(Message::DescriptionEdited(" a, edited ".into()));
+		assert!(commit_edits(&mut items));
+		assert_eq!(items[0].description, "a, edited");
+		assert!(!items[0].is_editing());
+
+		items[1].update(Message::Edit);
+		items[1].update(Message::DescriptionEdited(" ".into()));
+		assert!(!commit_edits(&mut items));
+		assert!(items[1].is_editing());
+	}
+
+	#[test]
+	fn the_rank_is_hidden_unless_shown_or_typed() {
+		let options = |show_index, typing_rank| RowOptions {
+			rank: 1,
+			score: None,
+			show_index,
+			badge: None,
+			density: Density::default(),
+			typing_rank,
+			show_count: false,
+			right_to_left: false,
+			can_move_up: false,
+			can_move_down: false,
+		};
+		assert_eq!(options(true, false).rank_cell(), RankCell::Shown);
+		assert_eq!(options(false, false).rank_cell(), RankCell::Hidden);
+		assert_eq!(options(false, true).rank_cell(), RankCell::Typed);
+		assert_eq!(options(true, true).rank_cell(), RankCell::Typed);
+	}
+
+	#[test]
+	fn a_list_written_by_hand_needs_only_ids_and_descriptions() {
+		let json = r#"[{"id": 7, "description": "Water plants", "notes": "Weekly"}]"#;
+		let loaded: Vec<Item> = serde_json::from_str(json).unwrap();
+		assert_eq!(loaded[0].id, 7);
+		assert_eq!(loaded[0].description, "Water plants");
+		assert_eq!(loaded[0].notes, "Weekly");
+		assert!(!loaded[0].recurring && !loaded[0].selected);
+		assert!(loaded[0].tags.is_empty() && loaded[0].children.is_empty());
 	}
 }
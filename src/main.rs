@@ -1,27 +1,112 @@
+mod archive;
+mod badge;
+mod cache;
+mod cli;
+mod export;
+mod field;
 mod item;
+mod merge_insertion;
+mod milestone;
+mod pause;
+mod replay;
+mod seed;
+mod session;
+mod settings;
+mod sorter;
+mod stability;
+mod storage;
+mod transition;
+mod typed_ranks;
+
+use std::{
+	cmp::Ordering,
+	collections::{HashSet, VecDeque},
+	env, fs, iter, mem,
+	path::PathBuf,
+	process,
+	time::{Duration, Instant, SystemTime},
+};
 
 use iced::{
-	alignment,
+	alignment, clipboard,
 	event::{self, Event},
 	keyboard::{self, KeyCode, Modifiers},
-	subscription,
-	theme::Theme,
-	widget::{self, button, column, container, row, scrollable, text, text_input},
-	window, Alignment, Application, Color, Command, Element, Length, Settings, Subscription,
+	mouse, subscription,
+	theme::{self, Theme},
+	time,
+	widget::{
+		button, checkbox, column, container, image, mouse_area, pick_list, progress_bar, row,
+		scrollable, text, text_input, tooltip, Row,
+	},
+	window, Alignment, Application, Color, Command, Element, Length, Subscription,
 };
 use once_cell::sync::Lazy;
 
-use crate::item::{Item, Message as ItemMessage};
+use crate::{
+	archive::Archive,
+	cache::ChoiceCache,
+	export::ExportFormat,
+	field::{Field, FieldOrder},
+	item::{
+		is_already_ranked, is_blank, tag_selected, Item, Message as ItemMessage, Picture,
+		RowOptions,
+	},
+	pause::Pause,
+	replay::Replay,
+	seed::SeedStrategy,
+	session::Session,
+	settings::{Density, Settings, ThemeMode, WindowGeometry},
+	sorter::{Choice, FinishKind, SortMode, SortState, SortStrategy, Sorter, SorterError},
+	transition::Transition,
+};
 
 static INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+static RATIONALE_INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+static PAIR_EDIT_INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+
+const EXAMPLE_ITEMS: [&str; 3] = [
+	"Answer emails",
+	"Plan the weekend trip",
+	"Fix the leaky tap",
+];
+
+const CHOICE_SUMMARY_LENGTH: usize = 80;
+const LEADERBOARD_SIZE: usize = 5;
+const LEADERBOARD_SUMMARY_LENGTH: usize = 40;
+const UPCOMING_SIZE: usize = 3;
+/// How many places Find Top sorts for.
+const TOP_K: usize = 5;
+
+/// The tag Shift+Space puts on both items of the current question.
+const HIGH_TAG: &str = "high";
+
+/// Tags marking the two groups compared by the A/B summary.
+const AB_GROUPS: (&str, &str) = ("A", "B");
 
 pub fn main() -> iced::Result {
-	App::run(Settings {
+	let args: Vec<String> = env::args().skip(1).collect();
+	if args.iter().any(|arg| arg == "--cli") {
+		let choices = args.iter().skip_while(|arg| *arg != "--choices").nth(1);
+		if let Err(error) = cli::run(choices.map(String::as_str)) {
+			eprintln!("{error}");
+			process::exit(1);
+		}
+		return Ok(());
+	}
+	let geometry = storage::load_window();
+	App::run(iced::Settings {
 		window: window::Settings {
-			size: (500, 800),
+			size: geometry.size,
+			position: geometry
+				.position
+				.map_or(window::Position::Default, |(x, y)| {
+					window::Position::Specific(x, y)
+				}),
 			..window::Settings::default()
 		},
-		..Settings::default()
+		exit_on_close_request: false,
+		flags: geometry,
+		..iced::Settings::default()
 	})
 }
 
@@ -35,6 +120,8 @@ struct App {
 enum AppMode {
 	List,
 	Choose,
+	Replay,
+	Split,
 }
 
 type ItemsList = Vec<Item>;
@@ -43,6 +130,93 @@ type ItemsList = Vec<Item>;
 struct State {
 	input_value: String,
 	items: Vec<Item>,
+	sorter: Sorter<Item>,
+	/// Where the list being sorted sits: empty for the top level, otherwise the index of each
+	/// parent on the way down to it.
+	sort_path: Vec<usize>,
+	/// Sub-item lists still waiting for their turn once the current sort finishes.
+	sort_queue: VecDeque<Vec<usize>>,
+	/// How many places the next sort of the top level ranks, when it doesn't rank them all.
+	top_k: Option<usize>,
+	replay: Option<Replay<Item>>,
+	settings: Settings,
+	/// Kept apart from the settings, so resetting them doesn't shrink the window on the next
+	/// close.
+	window: WindowGeometry,
+	show_all: bool,
+	/// Whether the comparisons of the finished sort are listed under the items.
+	show_history: bool,
+	/// Whether completed items are left out of the list.
+	hide_completed: bool,
+	/// The item being dragged to a new place.
+	dragging: Option<usize>,
+	/// The place the dragged item would be dropped at.
+	drop_target: Option<usize>,
+	export_path: Option<PathBuf>,
+	status: Option<String>,
+	split: Option<SplitEditor>,
+	/// Rank and score shown for each item after a sort, shared by items declared equal. Cleared
+	/// by anything that moves, adds or removes items.
+	ranks: Vec<(usize, f32)>,
+	cache: ChoiceCache,
+	focused: Option<text_input::Id>,
+	tag_input: String,
+	/// Only items with this tag are listed, and sorting only sorts those.
+	tag_filter: Option<String>,
+	/// Only items whose description contains this, ignoring case, are listed. Unlike the tag
+	/// filter it has no effect on sorting.
+	search_query: String,
+	/// The highest progress milestone the current sort has reached, in percent.
+	last_milestone: u8,
+	pause: Pause,
+	/// Whether each item shows a field for typing its rank by hand.
+	typing_ranks: bool,
+	inserter: Option<Inserter>,
+	pair_edit: Option<PairEdit>,
+	archive: Archive,
+	/// The current pair sliding in, if it still is.
+	transition: Option<Transition>,
+	/// Set while asking whether to put every setting back to its default.
+	confirm_reset: bool,
+	/// Set while asking whether to delete every selected item.
+	confirm_delete_selected: bool,
+	/// Set while asking whether to delete every item.
+	confirm_clear: bool,
+	/// Set while asking whether to throw a finished sort away for a new one.
+	confirm_restart: bool,
+	/// Items read from a JSON file, held while asking whether they join the list or replace it.
+	imported: Option<Vec<Item>>,
+	/// Whether the defaults for new items are shown for editing.
+	editing_template: bool,
+	/// Set while asking whether to close the window in the middle of a sort.
+	quit_warning: bool,
+	/// The first ranking, kept while the list is sorted again to check how consistent it is.
+	baseline: Option<Vec<Item>>,
+	/// A choice waiting for its reason to be given or skipped before it is applied.
+	pending_choice: Option<Choice>,
+	rationale_input: String,
+}
+
+/// The form for dropping a new item straight into the middle of a sort.
+#[derive(Debug, Default)]
+struct Inserter {
+	position: String,
+	description: String,
+}
+
+/// A fix to the description of one of the two items being compared, which leaves the choices
+/// made about it as they are.
+#[derive(Debug)]
+struct PairEdit {
+	id: u64,
+	description: String,
+}
+
+#[derive(Debug, Default)]
+struct SplitEditor {
+	index: usize,
+	lines: Vec<String>,
+	input: String,
 }
 
 trait CanCompare {
@@ -55,211 +229,3266 @@ impl CanCompare for ItemsList {
 	}
 }
 
+impl State {
+	/// Whether the list can be sorted right now, finishing any edit in progress first.
+	fn ready_to_sort(&mut self) -> bool {
+		if !self.items.can_compare() {
+			return false;
+		}
+		if !item::commit_edits(&mut self.items) {
+			self.status = Some("Give the item being edited a description before sorting".into());
+			return false;
+		}
+		true
+	}
+
+	/// Whether `item` passes the tag filter, if there is one, and isn't a completed item while
+	/// those are hidden.
+	fn is_shown(&self, item: &Item) -> bool {
+		!(self.hide_completed && item.completed)
+			&& self
+				.tag_filter
+				.as_deref()
+				.is_none_or(|tag| item.has_tag(tag))
+	}
+
+	/// Whether `item` is shown and, when searching, its description contains the search.
+	fn is_listed(&self, item: &Item) -> bool {
+		self.is_shown(item)
+			&& item
+				.description
+				.to_lowercase()
+				.contains(&self.search_query.trim().to_lowercase())
+	}
+
+	/// What the list view shows, with rows carrying the item's index in the full list, which is
+	/// what their messages carry.
+	fn list_content(&self) -> ListContent {
+		if self.items.is_empty() {
+			return ListContent::Empty;
+		}
+		let shown: Vec<usize> = (0..self.items.len())
+			.filter(|&i| self.is_listed(&self.items[i]))
+			.collect();
+		if !shown.is_empty() {
+			ListContent::Rows(shown)
+		} else if is_blank(&self.search_query) {
+			ListContent::NothingListed("No items have this tag.")
+		} else {
+			ListContent::NothingListed("No items match the search.")
+		}
+	}
+
+	/// The nearest item listed above `i`, or below it when not `up`, which moving `i` swaps
+	/// places with so the items filtered out stay where they are.
+	fn listed_neighbor(&self, i: usize, up: bool) -> Option<usize> {
+		self.items.get(i)?;
+		let listed = |j: &usize| self.is_listed(&self.items[*j]);
+		if up {
+			(0..i).rev().find(listed)
+		} else {
+			(i + 1..self.items.len()).find(listed)
+		}
+	}
+
+	/// The number shown for the item at `index`: its rank from the last sort while the list is as
+	/// that sort left it, and otherwise its place in the list, counted over every item so the
+	/// numbers don't shift when a filter hides some.
+	fn number(&self, index: usize) -> (usize, Option<f32>) {
+		match self.ranks.get(index) {
+			Some(&(rank, score)) if self.ranks.len() == self.items.len() => (rank, Some(score)),
+			_ => (index + 1, None),
+		}
+	}
+
+	/// Adds an item for each line of `text` that isn't blank or already in the list, and returns
+	/// how many were added and how many skipped.
+	fn add_lines(&mut self, text: &str) -> (usize, usize) {
+		let mut known: HashSet<String> = self
+			.items
+			.iter()
+			.map(|item| item::normalized(&item.description))
+			.collect();
+		let (mut added, mut skipped) = (0, 0);
+		for line in text.lines().filter(|line| !is_blank(line)) {
+			let item = self.settings.new_item_template.create(line);
+			if known.insert(item::normalized(&item.description)) {
+				self.items.push(item);
+				added += 1;
+			} else {
+				skipped += 1;
+			}
+		}
+		if added > 0 {
+			self.ranks.clear();
+			self.save_items();
+		}
+		(added, skipped)
+	}
+
+	/// Answers the sorter's questions for as long as the answer is already known or the two
+	/// items are duplicates.
+	fn skip_known(&mut self) {
+		loop {
+			self.cache.resolve(&mut self.sorter);
+			let duplicate = self.settings.auto_equal_duplicates
+				&& self
+					.sorter
+					.state
+					.current_pair()
+					.is_some_and(|(left, right)| {
+						item::normalized(&left.description) == item::normalized(&right.description)
+					});
+			if !duplicate || self.sorter.make_choice(Choice::Equal).is_err() {
+				break;
+			}
+		}
+	}
+
+	/// Forgets the sort in progress or last finished and everything kept about it, for when the
+	/// list it was about is replaced.
+	fn reset_sort(&mut self) {
+		self.sorter = Sorter::new();
+		self.sort_queue.clear();
+		self.sort_path.clear();
+		self.top_k = None;
+		self.pending_choice = None;
+		self.inserter = None;
+		self.pair_edit = None;
+		self.baseline = None;
+		self.ranks.clear();
+		self.cache = ChoiceCache::default();
+		self.pause.reset();
+		self.last_milestone = 0;
+	}
+
+	fn save_settings(&mut self) {
+		if let Err(error) = storage::save_settings(&self.settings) {
+			self.status = Some(format!("Couldn't save the settings: {}", error));
+		}
+	}
+
+	fn save_window(&mut self) {
+		if let Err(error) = storage::save_window(self.window) {
+			self.status = Some(format!("Couldn't save the window size: {}", error));
+		}
+	}
+
+	fn save_items(&mut self) {
+		if let Err(error) = storage::save_items(&self.items) {
+			self.status = Some(format!("Couldn't save the list: {}", error));
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ListContent {
+	/// There are no items at all.
+	Empty,
+	/// Every item is filtered out, with a note saying why.
+	NothingListed(&'static str),
+	Rows(Vec<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListRow {
+	Item(usize),
+	Elided(usize),
+}
+
+fn empty_state<'a>() -> Element<'a, Message> {
+	let examples = EXAMPLE_ITEMS
+		.iter()
+		.fold(Row::new().spacing(10), |examples, example| {
+			examples.push(
+				button(text(*example))
+					.on_press(Message::InputChanged((*example).to_string()))
+					.style(theme::Button::Secondary),
+			)
+		});
+
+	column![
+		text("Nothing to prioritize yet.").size(30),
+		text("Type something above and press Enter, or start from an example:")
+			.style(Color::from([0.5, 0.5, 0.5])),
+		examples,
+	]
+	.spacing(20)
+	.width(Length::Fill)
+	.align_items(Alignment::Center)
+	.into()
+}
+
+/// What a choice button shows for `item`: its picture, if it has one, above its summary, notes,
+/// due date and tags.
+fn choice_content<'a>(item: &Item) -> Element<'a, Message> {
+	let mut summary = column![text(item.summary(CHOICE_SUMMARY_LENGTH))]
+		.spacing(5)
+		.align_items(Alignment::Center);
+	if !is_blank(&item.notes) {
+		summary = summary.push(text(item.notes.trim()).size(18));
+	}
+	if let Some(due) = item.due {
+		summary = summary.push(text(format!("Due {}", due)).size(16));
+	}
+	if !item.tags.is_empty() {
+		let tags = item
+			.tags
+			.iter()
+			.map(|tag| format!("#{}", tag))
+			.collect::<Vec<_>>()
+			.join(" ");
+		summary = summary.push(text(tags).size(16).style(Color::from([0.5, 0.5, 0.5])));
+	}
+	let summary: Element<_> = summary.into();
+	match item.picture() {
+		Picture::Found(path) => column![
+			image(image::Handle::from_path(path))
+				.width(Length::Fill)
+				.height(200),
+			summary,
+		]
+		.spacing(10)
+		.align_items(Alignment::Center)
+		.into(),
+		Picture::Missing => column![
+			text("(image not found)").style(Color::from([0.5, 0.5, 0.5])),
+			summary,
+		]
+		.spacing(10)
+		.align_items(Alignment::Center)
+		.into(),
+		Picture::None => summary,
+	}
+}
+
+/// The inputs Tab moves between in each mode, so focus never lands on a screen that is not shown.
+fn focus_order(mode: &AppMode, items: &[Item]) -> Vec<text_input::Id> {
+	match mode {
+		AppMode::List => iter::once(INPUT_ID.clone())
+			.chain(
+				items
+					.iter()
+					.enumerate()
+					.filter(|(_, item)| item.is_editing())
+					.map(|(i, _)| Item::text_input_id(&i)),
+			)
+			.collect(),
+		AppMode::Split => vec![INPUT_ID.clone()],
+		AppMode::Choose | AppMode::Replay => Vec::new(),
+	}
+}
+
+fn cycle_focus(
+	order: &[text_input::Id],
+	current: Option<&text_input::Id>,
+	backwards: bool,
+) -> Option<text_input::Id> {
+	let position = current.and_then(|current| order.iter().position(|id| id == current));
+	let next = match (position, backwards) {
+		(Some(i), false) => (i + 1) % order.len(),
+		(Some(i), true) => (i + order.len() - 1) % order.len(),
+		(None, false) => 0,
+		(None, true) => order.len().checked_sub(1)?,
+	};
+	order.get(next).cloned()
+}
+
+/// Maps a list of `len` items to the rows shown, keeping the first and last `k` items and
+/// replacing the middle with a single elided row.
+fn collapsed_rows(len: usize, k: usize, show_all: bool) -> Vec<ListRow> {
+	if show_all || len <= k * 2 {
+		return (0..len).map(ListRow::Item).collect();
+	}
+
+	(0..k)
+		.map(ListRow::Item)
+		.chain([ListRow::Elided(len - k * 2)])
+		.chain((len - k..len).map(ListRow::Item))
+		.collect()
+}
+
 #[derive(Debug, Clone)]
 enum Message {
-	ToggleView,
+	SortItems,
+	FindTopK,
+	RestartSort,
+	CancelRestartSort,
+	PlaceNewItems,
+	SortByField {
+		field: Field,
+		ascending: bool,
+	},
+	CheckConsistency,
+	RefineItems,
+	ChooseLeft,
+	ChooseRight,
+	ChooseEqual,
+	ToggleInserter,
+	InsertPositionChanged(String),
+	InsertDescriptionChanged(String),
+	InsertAt(usize, String),
+	/// Starts editing the item on that side of the comparison, as shown.
+	EditPairItem(Choice),
+	PairEditChanged(String),
+	SavePairEdit,
+	CancelPairEdit,
+	UndoChoice,
+	TagPairHigh,
+	BreakTick,
+	Frame(Instant),
+	SwapSides,
+	ToggleRationale(bool),
+	ToggleAutoEqual(bool),
+	ToggleReduceMotion(bool),
+	RationaleChanged(String),
+	SubmitRationale,
+	SkipRationale,
+	ListView,
+	ReplayLastSort,
+	CopyTranscript,
+	ToggleHistory,
+	ToggleShowCompleted,
+	DragStart(usize),
+	DragOver(usize),
+	Drop,
+	CopyHistory,
+	CopyMarkdown,
+	CopyCsv,
+	CopyOutline,
+	CopyAbSummary,
+	ReplayForward,
+	ReplayBack,
+	ToggleShowAll,
+	ToggleDensity,
+	ToggleTheme,
+	ToggleCounts(bool),
+	ToggleIndices(bool),
+	ToggleRightToLeft(bool),
+	ToggleTemplate,
+	TemplatePrefixChanged(String),
+	TemplateTagsChanged(String),
+	TemplateNotesChanged(String),
+	AskResetSettings,
+	CancelResetSettings,
+	ResetSettings,
+	ToggleBalancedSeed(bool),
+	ToggleAlternateEnds(bool),
+	ToggleShuffleStart(bool),
+	ToggleMergeInsertion(bool),
+	ToggleTypedRanks,
+	ApplyTypedRanks,
+	QuickExport,
+	ExportTo(Option<PathBuf>),
+	ExportFormatSelected(ExportFormat),
+	SaveSession,
+	SaveSessionTo(Option<PathBuf>),
+	OpenSession,
+	SessionPicked(Option<PathBuf>),
+	ReplaySession,
+	ReplaySessionPicked(Option<PathBuf>),
+	ImportFile,
+	ImportPicked(Option<PathBuf>),
+	ExportJson,
+	ExportJsonTo(Option<PathBuf>),
+	ImportJson,
+	ImportJsonPicked(Option<PathBuf>),
+	AddImported,
+	ReplaceWithImported,
+	CancelImport,
+	PasteItems,
+	ItemsPasted(Option<String>),
 	InputChanged(String),
 	CreateTask,
 	ItemMessage(usize, ItemMessage),
-	TabPressed { shift: bool },
+	TagInputChanged(String),
+	FilterByTag(Option<String>),
+	SearchChanged(String),
+	TagSelected(String),
+	ArchiveSelected,
+	AskClearAll,
+	CancelClearAll,
+	ClearAll,
+	AskDeleteSelected,
+	CancelDeleteSelected,
+	DeleteSelected,
+	UndoArchive,
+	SplitItem(usize),
+	ImagePicked(usize, Option<PathBuf>),
+	SplitInputChanged(String),
+	SplitLineAdded,
+	ConfirmSplit,
+	TabPressed {
+		shift: bool,
+	},
 	ToggleFullscreen(window::Mode),
+	WindowResized(u32, u32),
+	WindowMoved(i32, i32),
+	CloseRequested,
+	/// Leaves a sort for the list with everything about it kept, to be picked up again.
+	PauseSort,
+	ResumeSort,
+	/// Ends a sort with its best guess so far: the placed items in their order, then the ones
+	/// not placed yet in their original order. Only ever on explicit request, as it throws the
+	/// rest of the sort away.
+	FinishEarly,
+	ConfirmQuit,
+	CancelQuit,
+	ToggleQuitWarning(bool),
 }
 
-impl Application for App {
-	type Executor = iced::executor::Default;
-	type Flags = ();
-	type Message = Message;
-	type Theme = Theme;
+impl App {
+	/// Quick export only makes sense once a ranking has been produced.
+	fn can_quick_export(&self) -> bool {
+		matches!(self.mode, AppMode::List) && matches!(self.state.sorter.state, SortState::Done(_))
+	}
 
-	fn new(_flags: ()) -> (App, Command<Message>) {
-		let state = State {
-			input_value: "".into(),
-			items: Vec::new(),
-		};
-		(
-			App {
-				state,
-				mode: AppMode::List,
+	fn export_to(&mut self, path: PathBuf) {
+		let state = &mut self.state;
+		match fs::write(&path, state.settings.export_format.export(&state.items)) {
+			Ok(()) => {
+				state.status = Some(format!("Exported to {}", path.display()));
+				state.export_path = Some(path);
 			},
-			Command::none(),
-		)
+			Err(error) => state.status = Some(format!("Export failed: {}", error)),
+		}
 	}
 
-	fn title(&self) -> String {
-		"Priority Sorter".into()
-	}
+	fn list_view(&self) -> Element<Message> {
+		let input_value = &self.state.input_value;
 
-	fn update(&mut self, message: Message) -> Command<Message> {
-		let state = &mut self.state;
-		match message {
-			Message::InputChanged(value) => {
-				state.input_value = value;
+		let items = &self.state.items;
 
-				Command::none()
-			},
-			Message::CreateTask => {
-				if !state.input_value.is_empty() {
-					state
-						.items
-						.insert(state.items.len(), Item::new(state.input_value.clone()));
-					state.input_value.clear();
-				}
+		let title = text("Priorities")
+			.width(Length::Fill)
+			.size(100)
+			.style(Color::from([0.5, 0.5, 0.5]))
+			.horizontal_alignment(alignment::Horizontal::Center);
 
-				Command::none()
-			},
-			Message::ItemMessage(i, ItemMessage::Delete) => {
-				state.items.remove(i);
+		let input = text_input("What would you like to prioritize?", input_value)
+			.id(INPUT_ID.clone())
+			.on_input(Message::InputChanged)
+			.on_submit(Message::CreateTask)
+			.padding(15)
+			.size(30);
 
-				Command::none()
-			},
-			Message::ItemMessage(i, item_message) => {
-				if let Some(item) = state.items.get_mut(i) {
-					let should_focus = matches!(item_message, ItemMessage::Edit);
+		let density = self.state.settings.density;
+		let threshold = self.state.settings.collapse_threshold;
+		let content = self.state.list_content();
+		let shown: &[usize] = match &content {
+			ListContent::Rows(shown) => shown,
+			ListContent::Empty | ListContent::NothingListed(_) => &[],
+		};
+		let rows = collapsed_rows(shown.len(), threshold, self.state.show_all);
+		let collapsible = shown.len() > threshold * 2;
 
-					item.update(item_message);
+		let items_list: Element<_> = match content {
+			ListContent::Empty => empty_state(),
+			ListContent::NothingListed(note) => text(note)
+				.width(Length::Fill)
+				.style(Color::from([0.5, 0.5, 0.5]))
+				.horizontal_alignment(alignment::Horizontal::Center)
+				.into(),
+			ListContent::Rows(_) => column(
+				rows.into_iter()
+					.map(|row| match row {
+						ListRow::Item(row) => {
+							let i = shown[row];
+							let (rank, score) = self.state.number(i);
+							let badge = score.map(|_| {
+								badge::rank_to_badge(
+									rank,
+									items.len(),
+									&self.state.settings.badge_cutoffs,
+								)
+							});
+							let view = items[i]
+								.view(
+									i,
+									RowOptions {
+										rank,
+										score,
+										show_index: self.state.settings.show_indices,
+										badge,
+										density,
+										typing_rank: self.state.typing_ranks,
+										show_count: self.state.settings.show_counts,
+										right_to_left: self.state.settings.right_to_left,
+										can_move_up: row > 0,
+										can_move_down: row + 1 < shown.len(),
+									},
+								)
+								.map(move |message| match message {
+									ItemMessage::Split => Message::SplitItem(i),
+									message => Message::ItemMessage(i, message),
+								});
+							mouse_area(view)
+								.on_press(Message::DragStart(i))
+								.on_release(Message::DragOver(i))
+								.into()
+						},
+						ListRow::Elided(count) => text(format!("…{} more…", count))
+							.width(Length::Fill)
+							.style(Color::from([0.5, 0.5, 0.5]))
+							.horizontal_alignment(alignment::Horizontal::Center)
+							.into(),
+					})
+					.collect(),
+			)
+			.spacing(density.item_spacing())
+			.into(),
+		};
 
-					if should_focus {
-						let id = Item::text_input_id(&i);
-						Command::batch(vec![
-							text_input::focus(id.clone()),
-							text_input::select_all(id),
-						])
-					} else {
-						Command::none()
+		let replay = self
+			.state
+			.sorter
+			.log()
+			.filter(|log| !log.choices.is_empty())
+			.map(|log| {
+				let tagged = |tag: &str| {
+					log.items
+						.iter()
+						.any(|item| item.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+				};
+				let mut actions = row![
+					button("Replay Last Sort").on_press(Message::ReplayLastSort),
+					button("Copy Transcript").on_press(Message::CopyTranscript),
+				]
+				.spacing(density.spacing());
+				if tagged(AB_GROUPS.0) && tagged(AB_GROUPS.1) {
+					actions =
+						actions.push(button("Copy A/B Summary").on_press(Message::CopyAbSummary));
+				}
+				actions
+			});
+
+		let mut controls = Row::new().spacing(density.spacing());
+		if self.state.sorter.state.current_pair().is_some() {
+			// Starting another sort now would throw the paused one away.
+			controls = controls.push(button("Resume Sort").on_press(Message::ResumeSort));
+		} else if self.state.items.can_compare() {
+			let sorter = &self.state.sorter.state;
+			let already_sorted =
+				matches!(sorter, SortState::Done(_)) && is_already_ranked(items, sorter.placed());
+			let sort = if already_sorted {
+				button("Already Sorted")
+			} else {
+				button("Sort Items").on_press(Message::SortItems)
+			};
+			controls = controls.push(sort);
+			if !already_sorted && items.iter().filter(|item| !item.completed).count() > TOP_K {
+				controls = controls
+					.push(button(text(format!("Find Top {}", TOP_K))).on_press(Message::FindTopK));
+			}
+			// The list can't be told apart from the ranking while filtered.
+			if self.state.tag_filter.is_none()
+				&& matches!(sorter, SortState::Done(_))
+				&& item::has_new_items(items, sorter.placed())
+			{
+				controls =
+					controls.push(button("Place New Items").on_press(Message::PlaceNewItems));
+			}
+			if !already_sorted {
+				let count = items
+					.iter()
+					.filter(|item| !item.completed && self.state.is_shown(item))
+					.count();
+				let estimate = self.state.settings.sort_strategy.max_comparisons(count);
+				controls = controls.push(
+					text(format!("up to {} choices", estimate)).style(Color::from([0.5, 0.5, 0.5])),
+				);
+			}
+			controls = controls.push(button("Refine Order").on_press(Message::RefineItems));
+			if already_sorted {
+				controls =
+					controls.push(button("Check Consistency").on_press(Message::CheckConsistency));
+			}
+		} else {
+			controls = controls
+				.push(text("Add at least two items to sort").style(Color::from([0.5, 0.5, 0.5])));
+		}
+		if let Some(replay) = replay {
+			controls = controls.push(replay);
+		}
+		if items.can_compare() {
+			controls = controls.push(
+				pick_list(FieldOrder::all(), None, |order: FieldOrder| {
+					Message::SortByField {
+						field: order.field,
+						ascending: order.ascending,
 					}
+				})
+				.placeholder("Order by..."),
+			);
+		}
+		if self.state.typing_ranks {
+			controls = controls
+				.push(button("Apply Ranks").on_press(Message::ApplyTypedRanks))
+				.push(button("Cancel").on_press(Message::ToggleTypedRanks));
+		} else if items.can_compare() {
+			controls = controls.push(button("Type Ranks").on_press(Message::ToggleTypedRanks));
+		}
+		if !items.is_empty() {
+			controls = controls
+				.push(button("Copy as Markdown").on_press(Message::CopyMarkdown))
+				.push(button("Copy as CSV").on_press(Message::CopyCsv))
+				.push(
+					button("Clear All")
+						.on_press(Message::AskClearAll)
+						.style(theme::Button::Destructive),
+				);
+		}
+		if items.iter().any(|item| !item.children.is_empty()) {
+			controls = controls.push(button("Copy Outline").on_press(Message::CopyOutline));
+		}
+		if self.can_quick_export() {
+			controls = controls.push(pick_list(
+				&ExportFormat::ALL[..],
+				Some(self.state.settings.export_format),
+				Message::ExportFormatSelected,
+			));
+		}
+		controls = controls
+			.push(button("Open Session").on_press(Message::OpenSession))
+			.push(button("Replay Session").on_press(Message::ReplaySession))
+			.push(button("Import Text File").on_press(Message::ImportFile))
+			.push(button("Paste as Items").on_press(Message::PasteItems))
+			.push(button("Import JSON").on_press(Message::ImportJson))
+			.push(button("Export JSON").on_press(Message::ExportJson));
+		if collapsible {
+			let label = if self.state.show_all {
+				"Collapse"
+			} else {
+				"Show All"
+			};
+			controls = controls.push(button(label).on_press(Message::ToggleShowAll));
+		}
+		let density_label = match density {
+			Density::Comfortable => "Compact",
+			Density::Compact => "Comfortable",
+		};
+		let theme_label = match self.state.settings.theme {
+			ThemeMode::Light => "Dark",
+			ThemeMode::Dark => "Light",
+		};
+		if items.iter().any(|item| !item.tags.is_empty()) {
+			controls = controls.push(checkbox(
+				"Balance tags",
+				self.state.settings.seed_strategy == SeedStrategy::Balanced,
+				Message::ToggleBalancedSeed,
+			));
+		}
+		controls = controls.push(checkbox(
+			"Vary questions",
+			self.state.settings.alternate_ends,
+			Message::ToggleAlternateEnds,
+		));
+		controls = controls.push(checkbox(
+			"Shuffle first",
+			self.state.settings.shuffle_start,
+			Message::ToggleShuffleStart,
+		));
+		controls = controls.push(checkbox(
+			"Fewer questions",
+			self.state.settings.sort_strategy == SortStrategy::MergeInsertion,
+			Message::ToggleMergeInsertion,
+		));
+		controls = controls
+			.push(button("New Item Defaults").on_press(Message::ToggleTemplate))
+			.push(button("Reset Settings").on_press(Message::AskResetSettings))
+			.push(button(density_label).on_press(Message::ToggleDensity))
+			.push(button(theme_label).on_press(Message::ToggleTheme))
+			.push(checkbox(
+				"Counts",
+				self.state.settings.show_counts,
+				Message::ToggleCounts,
+			))
+			.push(checkbox(
+				"Numbers",
+				self.state.settings.show_indices,
+				Message::ToggleIndices,
+			))
+			.push(checkbox(
+				"Right to left",
+				self.state.settings.right_to_left,
+				Message::ToggleRightToLeft,
+			));
+
+		let status = text(self.state.status.as_deref().unwrap_or_default())
+			.style(Color::from([0.5, 0.5, 0.5]));
+
+		let mut content = column![title, input, controls, status];
+		if self.state.editing_template {
+			let template = &self.state.settings.new_item_template;
+			content = content.push(
+				row![
+					text_input("Description prefix", &template.prefix)
+						.on_input(Message::TemplatePrefixChanged)
+						.padding(10),
+					text_input("Tags, separated by spaces", &template.tags)
+						.on_input(Message::TemplateTagsChanged)
+						.padding(10),
+					text_input("Notes", &template.notes)
+						.on_input(Message::TemplateNotesChanged)
+						.padding(10),
+					button("Done").on_press(Message::ToggleTemplate),
+				]
+				.spacing(density.spacing())
+				.align_items(Alignment::Center),
+			);
+		}
+		if let Some(imported) = &self.state.imported {
+			content = content.push(
+				row![
+					text(format!(
+						"Add the {} imported items to the list, or replace the list with them?",
+						imported.len()
+					)),
+					button("Add").on_press(Message::AddImported),
+					button("Replace")
+						.on_press(Message::ReplaceWithImported)
+						.style(theme::Button::Destructive),
+					button("Cancel").on_press(Message::CancelImport),
+				]
+				.spacing(density.spacing())
+				.align_items(Alignment::Center),
+			);
+		}
+		if self.state.confirm_restart {
+			content = content.push(
+				row![
+					text("Restart sorting from scratch? The finished sort will be replaced."),
+					button("Restart")
+						.on_press(Message::RestartSort)
+						.style(theme::Button::Destructive),
+					button("Cancel").on_press(Message::CancelRestartSort),
+				]
+				.spacing(density.spacing())
+				.align_items(Alignment::Center),
+			);
+		}
+		if self.state.confirm_clear {
+			content = content.push(
+				row![
+					text(format!(
+						"Delete all {} items and any sort in progress? This can't be undone.",
+						items.len()
+					)),
+					button("Clear All")
+						.on_press(Message::ClearAll)
+						.style(theme::Button::Destructive),
+					button("Cancel").on_press(Message::CancelClearAll),
+				]
+				.spacing(density.spacing())
+				.align_items(Alignment::Center),
+			);
+		}
+		if self.state.confirm_reset {
+			content = content.push(
+				row![
+					text("Reset all settings to their defaults? Your items stay as they are."),
+					button("Reset")
+						.on_press(Message::ResetSettings)
+						.style(theme::Button::Destructive),
+					button("Cancel").on_press(Message::CancelResetSettings),
+				]
+				.spacing(density.spacing())
+				.align_items(Alignment::Center),
+			);
+		}
+		if items.iter().any(|item| item.selected) {
+			let tag_input = &self.state.tag_input;
+			content = content.push(
+				row![
+					text_input("Tag for the selected items", tag_input)
+						.on_input(Message::TagInputChanged)
+						.on_submit(Message::TagSelected(tag_input.clone()))
+						.padding(10),
+					button("Apply Tag").on_press(Message::TagSelected(tag_input.clone())),
+					button("Archive Selected").on_press(Message::ArchiveSelected),
+					button("Delete Selected")
+						.on_press(Message::AskDeleteSelected)
+						.style(theme::Button::Destructive),
+				]
+				.spacing(density.spacing())
+				.align_items(Alignment::Center),
+			);
+			if self.state.confirm_delete_selected {
+				let selected = items.iter().filter(|item| item.selected).count();
+				content = content.push(
+					row![
+						text(format!(
+							"Delete {} selected items? This can't be undone.",
+							selected
+						)),
+						button("Delete")
+							.on_press(Message::DeleteSelected)
+							.style(theme::Button::Destructive),
+						button("Cancel").on_press(Message::CancelDeleteSelected),
+					]
+					.spacing(density.spacing())
+					.align_items(Alignment::Center),
+				);
+			}
+		}
+		let mut tags: Vec<&str> = items
+			.iter()
+			.flat_map(|item| item.tags.iter().map(String::as_str))
+			.chain(self.state.tag_filter.as_deref())
+			.collect();
+		tags.sort_unstable();
+		tags.dedup();
+		if !tags.is_empty() {
+			let filter_button = |label: String, filter: Option<&str>| {
+				let style = if self.state.tag_filter.as_deref() == filter {
+					theme::Button::Primary
 				} else {
-					Command::none()
-				}
-			},
-			Message::TabPressed { shift } => {
-				if shift {
-					widget::focus_previous()
+					theme::Button::Secondary
+				};
+				button(text(label))
+					.on_press(Message::FilterByTag(filter.map(str::to_string)))
+					.style(style)
+			};
+			content = content.push(
+				tags.into_iter()
+					.fold(
+						row![text("Show:"), filter_button("All".into(), None)],
+						|filters, tag| filters.push(filter_button(format!("#{}", tag), Some(tag))),
+					)
+					.spacing(density.spacing())
+					.align_items(Alignment::Center),
+			);
+		}
+		let completed = items.iter().filter(|item| item.completed).count();
+		if completed > 0 {
+			let label = if self.state.hide_completed {
+				format!("Show Completed ({})", completed)
+			} else {
+				format!("Hide Completed ({})", completed)
+			};
+			content = content.push(button(text(label)).on_press(Message::ToggleShowCompleted));
+		}
+		if self.state.archive.can_undo() {
+			content = content.push(button("Undo Archive").on_press(Message::UndoArchive));
+		}
+
+		let archived = self.state.archive.items();
+		let archived = if archived.is_empty() {
+			column![]
+		} else {
+			archived.iter().fold(
+				column![text(format!("Archived ({})", archived.len()))].spacing(5),
+				|archived, item| {
+					archived
+						.push(text(item.description.as_str()).style(Color::from([0.5, 0.5, 0.5])))
+				},
+			)
+		};
+
+		let history = self.state.sorter.history();
+		let history =
+			if matches!(self.state.sorter.state, SortState::Done(_)) && !history.is_empty() {
+				let label = if self.state.show_history {
+					format!("Hide Comparisons ({})", history.len())
 				} else {
-					widget::focus_next()
-				}
-			},
-			Message::ToggleFullscreen(mode) => window::change_mode(mode),
-			Message::ToggleView => {
-				match self.mode {
-					AppMode::List => {
-						if self.state.items.can_compare() {
-							self.mode = AppMode::Choose
-						}
-					},
-					AppMode::Choose => self.mode = AppMode::List,
+					format!("Show Comparisons ({})", history.len())
+				};
+				let toggle = row![
+					button(text(label))
+						.on_press(Message::ToggleHistory)
+						.style(theme::Button::Text),
+					button("Copy").on_press(Message::CopyHistory),
+				]
+				.spacing(density.spacing())
+				.align_items(Alignment::Center);
+				if self.state.show_history {
+					history.iter().enumerate().fold(
+						column![toggle].spacing(5),
+						|history, (i, (left, right, choice))| {
+							history.push(
+								text(format!(
+									"{}. {}",
+									i + 1,
+									export::comparison(left, right, *choice)
+								))
+								.style(Color::from([0.5, 0.5, 0.5])),
+							)
+						},
+					)
+				} else {
+					column![toggle]
 				}
-				Command::none()
-			},
-		}
-	}
+			} else {
+				column![]
+			};
 
-	fn view(&self) -> Element<Message> {
-		let input_value = &self.state.input_value;
+		// Peeking at a paused sort shows the order it has got to.
+		let provisional = if self.state.sorter.state.current_pair().is_some() {
+			self.state
+				.sorter
+				.current_ranking()
+				.into_iter()
+				.enumerate()
+				.fold(
+					column![text("Order so far").size(20)].spacing(2),
+					|provisional, (i, item)| {
+						provisional.push(
+							text(format!(
+								"{}. {}",
+								i + 1,
+								item.summary(LEADERBOARD_SUMMARY_LENGTH)
+							))
+							.style(Color::from([0.5, 0.5, 0.5])),
+						)
+					},
+				)
+		} else {
+			column![]
+		};
 
-		let items = &self.state.items;
+		if !items.is_empty() {
+			content = content.push(
+				text_input("Search...", &self.state.search_query)
+					.on_input(Message::SearchChanged)
+					.padding(10),
+			);
+		}
 
-		let make_container = |content| {
-			scrollable(
-				container(content)
+		let drop_end: Element<_> = if self.state.dragging.is_some() {
+			mouse_area(
+				text("Release here to move it to the end")
 					.width(Length::Fill)
-					.padding(40)
-					.center_x(),
+					.style(Color::from([0.5, 0.5, 0.5]))
+					.horizontal_alignment(alignment::Horizontal::Center),
 			)
+			.on_release(Message::DragOver(items.len()))
+			.into()
+		} else {
+			column![].into()
 		};
 
-		match self.mode {
-			AppMode::List => {
-				let title = text("Priorities")
-					.width(Length::Fill)
-					.size(100)
-					.style(Color::from([0.5, 0.5, 0.5]))
-					.horizontal_alignment(alignment::Horizontal::Center);
+		content
+			.push(items_list)
+			.push(drop_end)
+			.push(provisional)
+			.push(history)
+			.push(archived)
+			.spacing(density.spacing())
+			.max_width(800)
+			.into()
+	}
 
-				let input = text_input("What would you like to prioritize?", input_value)
-					.id(INPUT_ID.clone())
-					.on_input(Message::InputChanged)
-					.on_submit(Message::CreateTask)
-					.padding(15)
-					.size(30);
+	fn choose_view(&self) -> Element<Message> {
+		let prompt = match self.state.sort_path.split_last() {
+			Some((&i, parents)) => item::list_at(&self.state.items, parents)
+				.and_then(|list| list.get(i))
+				.map_or_else(
+					|| "Which one is higher priority?".to_string(),
+					|parent| {
+						format!(
+							"Within \"{}\", which one is higher priority?",
+							parent.summary(CHOICE_SUMMARY_LENGTH)
+						)
+					},
+				),
+			None => match self.state.sorter.top_k() {
+				Some(k) => format!("Which one is higher priority? (Finding the top {})", k),
+				None => "Which one is higher priority?".to_string(),
+			},
+		};
+		// The item being placed is the last one waiting.
+		let prompt = match self.state.sorter.state.waiting().len() {
+			0 => prompt,
+			1 => format!("{} (Placing the last item)", prompt),
+			2 => format!("{} (1 item left to place)", prompt),
+			waiting => format!("{} ({} items left to place)", prompt, waiting - 1),
+		};
+		let prompt_text = text(prompt)
+			.width(Length::Fill)
+			.size(48)
+			.style(Color::from([0.5, 0.5, 0.5]))
+			.horizontal_alignment(alignment::Horizontal::Center);
 
-				let items_list: Element<_> = column(
-					items
-						.iter()
-						.enumerate()
-						.map(|(i, item)| {
-							item.view(i)
-								.map(move |message| Message::ItemMessage(i, message))
-						})
-						.collect(),
-				)
-				.spacing(10)
-				.into();
-
-				let content = if self.state.items.can_compare() {
-					column![
-						title,
-						input,
-						button("Sort Items").on_press(Message::ToggleView),
-						items_list
-					]
-				} else {
-					column![title, input, items_list]
-				}
-				.spacing(20)
-				.max_width(800);
+		let pair = self.state.sorter.state.current_pair().map(|(left, right)| {
+			if self.state.settings.left_is_incoming {
+				(left, right)
+			} else {
+				(right, left)
+			}
+		});
 
-				make_container(content).into()
-			},
-			AppMode::Choose => {
-				let prompt_text = text("Which one is higher priority?")
-					.width(Length::Fill)
-					.size(48)
-					.style(Color::from([0.5, 0.5, 0.5]))
-					.horizontal_alignment(alignment::Horizontal::Center);
+		let paused = self.state.pause.is_active();
+		let choice_button = |item: &Item, message| {
+			let mut choice = button(choice_content(item)).padding(20).width(Length::Fill);
+			if !paused {
+				choice = choice.on_press(message);
+			}
+			tooltip(choice, item.details(), tooltip::Position::Bottom).style(theme::Container::Box)
+		};
+
+		let mut equal = button("Equal").padding(20).style(theme::Button::Secondary);
+		if !paused {
+			equal = equal.on_press(Message::ChooseEqual);
+		}
 
-				let choices = container(
-					row![button("choice a"), button("choice b")]
+		let choices: Element<_> = match pair {
+			Some(_) if self.state.inserter.is_some() => self.insert_view(),
+			Some(_) if self.state.pair_edit.is_some() => self.pair_edit_view(),
+			Some(_) if self.state.pending_choice.is_some() => column![
+				text("Why? (optional)"),
+				text_input("A one-line reason...", &self.state.rationale_input)
+					.id(RATIONALE_INPUT_ID.clone())
+					.on_input(Message::RationaleChanged)
+					.on_submit(Message::SubmitRationale)
+					.padding(15),
+				row![
+					button("Submit").on_press(Message::SubmitRationale),
+					button("Skip")
+						.on_press(Message::SkipRationale)
+						.style(theme::Button::Secondary),
+				]
+				.spacing(20),
+			]
+			.spacing(20)
+			.align_items(Alignment::Center)
+			.into(),
+			Some((left, right)) => {
+				let offset = self
+					.state
+					.transition
+					.map_or(0.0, |transition| transition.offset());
+				// Each button keeps its own message, so mirroring the row never changes which item
+				// a press picks.
+				let choices = if self.state.settings.right_to_left {
+					row![
+						choice_button(right, Message::ChooseRight),
+						equal,
+						choice_button(left, Message::ChooseLeft),
+					]
+				} else {
+					row![
+						choice_button(left, Message::ChooseLeft),
+						equal,
+						choice_button(right, Message::ChooseRight),
+					]
+				};
+				container(
+					choices
 						.spacing(40)
 						.align_items(Alignment::Start)
 						.width(Length::Fill),
 				)
-				.center_x();
+				.padding(if self.state.settings.right_to_left {
+					[0.0, offset, 0.0, 0.0]
+				} else {
+					[0.0, 0.0, 0.0, offset]
+				})
+				.center_x()
+				.into()
+			},
+			// Only shown if the sorter is emptied out from under this screen, since it's only
+			// entered with a question waiting.
+			None => text("There is nothing to compare.").into(),
+		};
 
-				let content = column![prompt_text, choices]
-					.align_items(Alignment::Center)
-					.spacing(60)
-					.width(Length::Fill)
-					.max_width(800);
+		let mut undo = button("Undo");
+		if self.state.sorter.can_undo() || self.state.pending_choice.is_some() {
+			undo = undo.on_press(Message::UndoChoice);
+		}
+		let comparisons = self.state.sorter.comparisons();
+		let finish: Element<_> = if self.state.settings.allows_finish(comparisons) {
+			button("Finish With Current Estimate")
+				.on_press(Message::FinishEarly)
+				.into()
+		} else {
+			let minimum = self.state.settings.min_comparisons.unwrap_or_default();
+			tooltip(
+				button("Finish With Current Estimate"),
+				format!(
+					"Make {} more choices before leaving",
+					minimum.saturating_sub(comparisons)
+				),
+				tooltip::Position::Top,
+			)
+			.style(theme::Container::Box)
+			.into()
+		};
+		let controls = row![
+			undo,
+			button("Swap Sides").on_press(Message::SwapSides),
+			button("Insert Item").on_press(Message::ToggleInserter),
+			button("Edit Left").on_press(Message::EditPairItem(
+				if self.state.settings.right_to_left {
+					Choice::Right
+				} else {
+					Choice::Left
+				}
+			)),
+			button("Edit Right").on_press(Message::EditPairItem(
+				if self.state.settings.right_to_left {
+					Choice::Left
+				} else {
+					Choice::Right
+				}
+			)),
+			button("Save Session").on_press(Message::SaveSession),
+			button("Pause")
+				.on_press(Message::PauseSort)
+				.style(theme::Button::Secondary),
+			finish,
+			checkbox(
+				"Explain choices",
+				self.state.settings.ask_rationale,
+				Message::ToggleRationale
+			),
+			checkbox(
+				"Skip duplicates",
+				self.state.settings.auto_equal_duplicates,
+				Message::ToggleAutoEqual
+			),
+			checkbox(
+				"Reduce motion",
+				self.state.settings.reduce_motion,
+				Message::ToggleReduceMotion
+			),
+		]
+		.spacing(20)
+		.align_items(Alignment::Center);
+
+		let progress = self.state.sorter.progress_detail();
+		let mut count = format!(
+			"{} choices made, {} placed, {} to go",
+			self.state.sorter.comparisons(),
+			progress.placed,
+			progress.waiting
+		);
+		if progress.settled > 0 {
+			count.push_str(&format!(", top {} settled", progress.settled));
+		}
+		let count = text(count).style(Color::from([0.5, 0.5, 0.5]));
 
-				make_container(content).into()
+		let celebration = if paused {
+			text(format!(
+				"Take a breather, choices unlock in {}s",
+				self.state.pause.remaining()
+			))
+		} else if self.state.last_milestone > 0 {
+			text(milestone::message(self.state.last_milestone))
+		} else {
+			text("")
+		}
+		.size(30);
+		let status = text(self.state.status.as_deref().unwrap_or_default());
+
+		let leaders = self.state.sorter.state.leaderboard(LEADERBOARD_SIZE);
+		let leaderboard = if leaders.is_empty() {
+			column![]
+		} else {
+			leaders.iter().enumerate().fold(
+				column![text("Top so far").size(20)].spacing(2),
+				|leaderboard, (i, item)| {
+					leaderboard.push(
+						text(format!(
+							"{}. {}",
+							i + 1,
+							item.summary(LEADERBOARD_SUMMARY_LENGTH)
+						))
+						.size(16)
+						.style(Color::from([0.5, 0.5, 0.5])),
+					)
+				},
+			)
+		};
+
+		let upcoming: Vec<_> = self.state.sorter.state.upcoming(UPCOMING_SIZE).collect();
+		let upcoming = if upcoming.is_empty() {
+			column![]
+		} else {
+			column![
+				text("Upcoming items").size(20),
+				upcoming
+					.into_iter()
+					.fold(row![].spacing(20), |upcoming, item| {
+						upcoming.push(
+							text(item.summary(LEADERBOARD_SUMMARY_LENGTH))
+								.size(16)
+								.style(Color::from([0.5, 0.5, 0.5])),
+						)
+					})
+			]
+			.spacing(2)
+			.align_items(Alignment::Center)
+		};
+
+		let progress = self.state.sorter.progress().map_or_else(
+			|| column![],
+			|(made, total)| {
+				column![
+					progress_bar(0.0..=total.max(1) as f32, made as f32).height(10),
+					text(format!("{} / ~{} comparisons", made, total))
+						.size(16)
+						.style(Color::from([0.5, 0.5, 0.5])),
+				]
+				.spacing(5)
+				.align_items(Alignment::Center)
 			},
+		);
+
+		column![
+			progress,
+			prompt_text,
+			choices,
+			controls,
+			count,
+			leaderboard,
+			upcoming,
+			celebration,
+			status
+		]
+		.align_items(Alignment::Center)
+		.spacing(60)
+		.width(Length::Fill)
+		.max_width(800)
+		.into()
+	}
+
+	/// Shows the items placed so far, so a forgotten item can be put exactly where it belongs.
+	fn insert_view(&self) -> Element<Message> {
+		let Some(inserter) = &self.state.inserter else {
+			return column![].into();
+		};
+
+		let placed = self
+			.state
+			.sorter
+			.state
+			.placed()
+			.iter()
+			.enumerate()
+			.fold(column![].spacing(5), |placed, (i, item)| {
+				placed.push(text(format!("{}. {}", i + 1, item.description)))
+			});
+
+		let position = inserter
+			.position
+			.trim()
+			.parse::<usize>()
+			.ok()
+			.filter(|&position| position > 0);
+		let mut insert = button("Insert");
+		if let Some(position) = position.filter(|_| !is_blank(&inserter.description)) {
+			insert = insert.on_press(Message::InsertAt(
+				position - 1,
+				inserter.description.trim().to_string(),
+			));
 		}
+
+		column![
+			scrollable(placed).height(200),
+			row![
+				text_input("Position", &inserter.position)
+					.on_input(Message::InsertPositionChanged)
+					.padding(10)
+					.width(100),
+				text_input("A forgotten item...", &inserter.description)
+					.on_input(Message::InsertDescriptionChanged)
+					.padding(10),
+				insert,
+				button("Cancel")
+					.on_press(Message::ToggleInserter)
+					.style(theme::Button::Secondary),
+			]
+			.spacing(20)
+			.align_items(Alignment::Center),
+		]
+		.spacing(20)
+		.into()
 	}
 
-	fn subscription(&self) -> Subscription<Message> {
-		subscription::events_with(|event, status| match (event, status) {
-			(
-				Event::Keyboard(keyboard::Event::KeyPressed {
-					key_code: keyboard::KeyCode::Tab,
-					modifiers,
-					..
-				}),
-				event::Status::Ignored,
-			) => Some(Message::TabPressed {
-				shift: modifiers.shift(),
-			}),
-			(
-				Event::Keyboard(keyboard::Event::KeyPressed {
-					key_code,
-					modifiers: Modifiers::SHIFT,
-				}),
-				event::Status::Ignored,
-			) => match key_code {
-				KeyCode::Up => Some(Message::ToggleFullscreen(window::Mode::Fullscreen)),
-				KeyCode::Down => Some(Message::ToggleFullscreen(window::Mode::Windowed)),
-				_ => None,
+	fn pair_edit_view(&self) -> Element<Message> {
+		let Some(edit) = &self.state.pair_edit else {
+			return column![].into();
+		};
+
+		let mut save = button("Save");
+		if !is_blank(&edit.description) {
+			save = save.on_press(Message::SavePairEdit);
+		}
+		column![
+			text("Fix the description. The choices made so far stay as they are."),
+			text_input("A description", &edit.description)
+				.id(PAIR_EDIT_INPUT_ID.clone())
+				.on_input(Message::PairEditChanged)
+				.on_submit(Message::SavePairEdit)
+				.padding(15),
+			row![
+				save,
+				button("Cancel")
+					.on_press(Message::CancelPairEdit)
+					.style(theme::Button::Secondary),
+			]
+			.spacing(20),
+		]
+		.spacing(20)
+		.align_items(Alignment::Center)
+		.into()
+	}
+
+	/// Keeps a sort of the whole list that is still asking questions for the next launch.
+	/// Returns whether it was kept.
+	fn save_sort(&self) -> bool {
+		self.state.sort_path.is_empty()
+			&& self.state.sort_queue.is_empty()
+			&& self.state.sorter.state.current_pair().is_some()
+			&& storage::save_sort(&self.state.sorter).is_ok()
+	}
+
+	/// Closing mid-sort loses the progress, so it's worth a second thought unless the user has
+	/// asked not to be warned.
+	fn should_warn_on_quit(&self) -> bool {
+		self.state.settings.warn_on_quit && self.state.sorter.state.current_pair().is_some()
+	}
+
+	fn quit_warning_view(&self) -> Element<Message> {
+		column![
+			text("A sort is still in progress").size(30),
+			text("Quitting now loses the choices made so far.").style(Color::from([0.5, 0.5, 0.5])),
+			row![
+				button("Quit")
+					.on_press(Message::ConfirmQuit)
+					.style(theme::Button::Destructive),
+				button("Keep Sorting").on_press(Message::CancelQuit),
+			]
+			.spacing(20),
+			checkbox(
+				"Don't show again",
+				!self.state.settings.warn_on_quit,
+				Message::ToggleQuitWarning
+			),
+		]
+		.spacing(20)
+		.width(Length::Fill)
+		.align_items(Alignment::Center)
+		.into()
+	}
+
+	fn replay_view(&self) -> Element<Message> {
+		let Some(replay) = &self.state.replay else {
+			return text("There is nothing to replay.").into();
+		};
+
+		let heading = text(format!(
+			"Replay: step {} of {}",
+			replay.step(),
+			replay.total_steps()
+		))
+		.width(Length::Fill)
+		.size(48)
+		.style(Color::from([0.5, 0.5, 0.5]))
+		.horizontal_alignment(alignment::Horizontal::Center);
+
+		let decision = match (replay.state().current_pair(), replay.next_choice()) {
+			(Some((left, right)), Some(Choice::Equal)) => format!(
+				"\"{}\" and \"{}\" were marked equal",
+				left.description, right.description
+			),
+			(Some((left, right)), Some(choice)) => {
+				let (winner, loser) = match choice {
+					Choice::Left => (left, right),
+					_ => (right, left),
+				};
+				format!(
+					"\"{}\" was chosen over \"{}\"",
+					winner.description, loser.description
+				)
 			},
-			_ => None,
-		})
+			_ => "No more decisions.".into(),
+		};
+		let decision = match replay.next_rationale() {
+			Some(rationale) => format!("{}, because: {}", decision, rationale),
+			None => decision,
+		};
+
+		let placed = column(
+			replay
+				.state()
+				.placed()
+				.iter()
+				.enumerate()
+				.map(|(i, item)| text(format!("{}. {}", i + 1, item.description)).into())
+				.collect(),
+		)
+		.spacing(10);
+
+		let mut previous = button("Previous");
+		if replay.step() > 0 {
+			previous = previous.on_press(Message::ReplayBack);
+		}
+		let mut next = button("Next");
+		if !replay.is_finished() {
+			next = next.on_press(Message::ReplayForward);
+		}
+		let controls = row![previous, next, button("Back").on_press(Message::ListView)].spacing(20);
+
+		column![heading, text(decision), controls, placed]
+			.align_items(Alignment::Center)
+			.spacing(40)
+			.width(Length::Fill)
+			.max_width(800)
+			.into()
+	}
+
+	fn split_view(&self) -> Element<Message> {
+		let Some(split) = &self.state.split else {
+			return text("There is nothing to split.").into();
+		};
+		let Some(item) = self.state.items.get(split.index) else {
+			return text("There is nothing to split.").into();
+		};
+
+		let heading = text(format!("Split \"{}\"", item.description))
+			.width(Length::Fill)
+			.size(48)
+			.style(Color::from([0.5, 0.5, 0.5]))
+			.horizontal_alignment(alignment::Horizontal::Center);
+
+		let input = text_input("Type a part and press Enter", &split.input)
+			.id(INPUT_ID.clone())
+			.on_input(Message::SplitInputChanged)
+			.on_submit(Message::SplitLineAdded)
+			.padding(15);
+
+		let lines = column(
+			split
+				.lines
+				.iter()
+				.enumerate()
+				.map(|(i, line)| text(format!("{}. {}", i + 1, line)).into())
+				.collect(),
+		)
+		.spacing(10);
+
+		let mut confirm = button("Split");
+		if !split.lines.is_empty() || !is_blank(&split.input) {
+			confirm = confirm.on_press(Message::ConfirmSplit);
+		}
+		let controls = row![confirm, button("Cancel").on_press(Message::ListView)].spacing(20);
+
+		column![heading, input, lines, controls]
+			.spacing(20)
+			.max_width(800)
+			.into()
+	}
+
+	fn save_session(&mut self, path: PathBuf) {
+		let state = &mut self.state;
+		let session = Session::new(
+			state.items.clone(),
+			state.sorter.log().cloned(),
+			state.sort_path.clone(),
+			state.sort_queue.iter().cloned().collect(),
+		);
+		let written = session
+			.to_json()
+			.map_err(|error| error.to_string())
+			.and_then(|json| fs::write(&path, json).map_err(|error| error.to_string()));
+		state.status = Some(match written {
+			Ok(()) => format!("Session saved to {}", path.display()),
+			Err(error) => format!("Could not save the session: {}", error),
+		});
+	}
+
+	fn export_json(&mut self, path: PathBuf) {
+		let state = &mut self.state;
+		let written = serde_json::to_string_pretty(&state.items)
+			.map_err(|error| error.to_string())
+			.and_then(|json| fs::write(&path, json).map_err(|error| error.to_string()));
+		state.status = Some(match written {
+			Ok(()) => format!("Items saved to {}", path.display()),
+			Err(error) => format!("Could not save the items: {}", error),
+		});
+	}
+
+	/// Reads the items stored at `path`, to be added to the list or replace it once the user says
+	/// which.
+	fn import_json(&mut self, path: PathBuf) {
+		let state = &mut self.state;
+		let items = fs::read_to_string(&path)
+			.map_err(|error| error.to_string())
+			.and_then(|json| {
+				serde_json::from_str::<Vec<Item>>(&json)
+					.map_err(|error| format!("not a list of items ({})", error))
+			});
+		match items {
+			Ok(items) if items.is_empty() => {
+				state.status = Some("The file has no items to import".into());
+			},
+			Ok(items) => {
+				state.imported = Some(items);
+				state.status = None;
+			},
+			Err(error) => state.status = Some(format!("Could not import the items: {}", error)),
+		}
+	}
+
+	/// Adds an item for each non-blank line of the text file at `path`, skipping any already in
+	/// the list.
+	fn import_file(&mut self, path: PathBuf) {
+		let state = &mut self.state;
+		let text = match fs::read_to_string(&path) {
+			Ok(text) => text,
+			Err(error) => {
+				state.status = Some(format!("Could not import the file: {}", error));
+				return;
+			},
+		};
+
+		let (imported, skipped) = state.add_lines(&text);
+		state.status = Some(format!(
+			"Imported {} items, skipped {} already in the list",
+			imported, skipped
+		));
+	}
+
+	/// Replaces the list and any sort in progress with the session stored at `path`.
+	fn open_session(&mut self, path: PathBuf) -> Command<Message> {
+		let state = &mut self.state;
+		let session = fs::read_to_string(&path)
+			.map_err(|error| error.to_string())
+			.and_then(|json| Session::from_json(&json).map_err(|error| error.to_string()));
+		let session = match session {
+			Ok(session) => session,
+			Err(error) => {
+				state.status = Some(format!("Could not open the session: {}", error));
+				return Command::none();
+			},
+		};
+
+		item::claim_ids(&session.items);
+		state.items = session.items;
+		state.reset_sort();
+		state.status = None;
+		let Some(log) = session.log else {
+			self.mode = AppMode::List;
+			return Command::none();
+		};
+		item::claim_ids(&log.items);
+		state.sorter = Sorter::from_log(log);
+		state.sorter.set_history_cap(state.settings.history_cap);
+		state.sort_path = session.sort_path;
+		state.sort_queue = session.sort_queue.into();
+		if matches!(state.sorter.state, SortState::Done(_)) {
+			self.mode = AppMode::List;
+			Command::none()
+		} else {
+			self.settle()
+		}
+	}
+
+	/// Steps through the sort saved in the session at `path`, leaving the list as it is.
+	fn replay_session(&mut self, path: PathBuf) {
+		let state = &mut self.state;
+		let session = fs::read_to_string(&path)
+			.map_err(|error| error.to_string())
+			.and_then(|json| Session::from_json(&json).map_err(|error| error.to_string()));
+		match session {
+			Ok(Session { log: Some(log), .. }) => {
+				state.replay = Some(Replay::new(log));
+				state.status = None;
+				self.mode = AppMode::Replay;
+			},
+			Ok(_) => state.status = Some("The session has no sort to replay".into()),
+			Err(error) => state.status = Some(format!("Could not open the session: {}", error)),
+		}
+	}
+
+	/// `choice` is the side of the screen that was picked, which is flipped back when the
+	/// incoming item is shown on the right.
+	fn choose(&mut self, choice: Choice) -> Command<Message> {
+		let state = &mut self.state;
+		let choice = if state.settings.left_is_incoming {
+			choice
+		} else {
+			choice.flipped()
+		};
+		if state.pause.is_active()
+			|| state.pending_choice.is_some()
+			|| state.inserter.is_some()
+			|| state.pair_edit.is_some()
+		{
+			return Command::none();
+		}
+		if state.settings.ask_rationale {
+			state.pending_choice = Some(choice);
+			state.rationale_input.clear();
+			return text_input::focus(RATIONALE_INPUT_ID.clone());
+		}
+
+		self.apply_choice(choice, None)
+	}
+
+	fn apply_choice(&mut self, choice: Choice, rationale: Option<String>) -> Command<Message> {
+		let state = &mut self.state;
+		// A tie goes to whichever item is due first, if either has a due date.
+		let choice = match state.sorter.state.current_pair() {
+			Some((left, right)) if choice == Choice::Equal => match left.due_first(right) {
+				Ordering::Less => Choice::Left,
+				Ordering::Greater => Choice::Right,
+				Ordering::Equal => Choice::Equal,
+			},
+			_ => choice,
+		};
+		if let Some((left, right)) = state.sorter.state.current_pair() {
+			state.cache.insert(left, right, choice);
+		}
+		state
+			.pause
+			.record_choice(state.settings.break_every, state.settings.break_seconds);
+		match state.sorter.make_choice(choice) {
+			Ok(()) => {
+				if let Some(rationale) = rationale {
+					state.sorter.explain_last_choice(rationale);
+				}
+			},
+			Err(error) => state.status = Some(format!("Can't choose, {}", error)),
+		}
+
+		self.settle()
+	}
+
+	/// Skips over comparisons that were already answered or are between duplicates, and returns
+	/// to the list once the sorter has nothing left to ask.
+	fn settle(&mut self) -> Command<Message> {
+		let state = &mut self.state;
+		state.skip_known();
+		if let Some(milestone) = milestone::crossed(state.last_milestone, state.sorter.completion())
+		{
+			state.last_milestone = milestone;
+			milestone::chime();
+		}
+		if state.sorter.state.current_pair().is_some() {
+			if !state.settings.reduce_motion {
+				state.transition = Some(Transition::new(Instant::now()));
+			}
+			self.mode = AppMode::Choose;
+			return Command::none();
+		}
+		if matches!(state.sorter.state, SortState::Empty) {
+			// Nothing was started, so there is nothing to settle.
+			self.mode = AppMode::List;
+			return Command::none();
+		}
+
+		let sorting = state.sorter.log().map(|log| log.mode) == Some(SortMode::Sort);
+		let checking = state.baseline.is_some();
+		// A sort of the items with one tag puts them back in the places they were taken from.
+		let mut whole = true;
+		if let Some(list) = item::list_at_mut(&mut state.items, &state.sort_path) {
+			let mut ranked = Vec::new();
+			if let FinishKind::Partial { placed, remaining } =
+				state.sorter.finish_sorting(&mut ranked)
+			{
+				state.status = Some(format!(
+					"The ranking is incomplete, only the top {} are in order and {} are unranked",
+					placed, remaining
+				));
+			}
+			let ids: HashSet<u64> = ranked.iter().map(|item| item.id).collect();
+			item::splice_ranked(list, ranked);
+			whole = list.iter().all(|item| ids.contains(&item.id));
+			for (i, parent) in list.iter_mut().enumerate() {
+				if !ids.contains(&parent.id) {
+					continue;
+				}
+				if state.settings.lock_ranked {
+					parent.description_locked = true;
+				}
+				if sorting && !checking && parent.children.can_compare() {
+					let mut path = state.sort_path.clone();
+					path.push(i);
+					state.sort_queue.push_back(path);
+				}
+			}
+		}
+		if !whole {
+			// Ranks from a sort of some of the items don't line up with the list.
+			state.ranks.clear();
+		} else if state.sort_path.is_empty() {
+			state.ranks = state
+				.sorter
+				.ranked_iter()
+				.map(|(rank, _, score)| (rank, score))
+				.collect();
+			if let Some(baseline) = state.baseline.take() {
+				state.status = Some(format!(
+					"Your two rankings agree with a correlation of {:.2}, where 1 is identical",
+					stability::order_correlation(&baseline, &state.items)
+				));
+			}
+		}
+		self.start_next_sort()
+	}
+
+	/// Starts sorting the next queued list that has anything to compare, parents before their
+	/// sub-items, or returns to the list when none is left.
+	fn start_next_sort(&mut self) -> Command<Message> {
+		let state = &mut self.state;
+		while let Some(path) = state.sort_queue.pop_front() {
+			let Some(list) = item::list_at(&state.items, &path) else {
+				continue;
+			};
+			// The filter picks what is sorted at the top, and a consistency check goes over all.
+			let filtered = path.is_empty() && state.baseline.is_none();
+			let mut items: ItemsList = list
+				.iter()
+				.filter(|item| !item.completed && (!filtered || state.is_shown(item)))
+				.cloned()
+				.collect();
+			if !items.can_compare() {
+				continue;
+			}
+			// A consistency check starts from the opposite end, so the second sort can't just
+			// follow the first. The list itself keeps its order until the result is in.
+			if state.baseline.is_some() && path.is_empty() {
+				items.reverse();
+			}
+			match state.settings.seed_strategy {
+				SeedStrategy::Decayed => {
+					seed::decayed(&mut items, state.settings.decay_per_day, SystemTime::now());
+				},
+				SeedStrategy::Balanced => seed::balanced(&mut items),
+			}
+			state.sorter.set_history_cap(state.settings.history_cap);
+			state.sorter.set_alternate(state.settings.alternate_ends);
+			state.sorter.set_strategy(state.settings.sort_strategy);
+			let started = match state.top_k.filter(|_| path.is_empty()) {
+				Some(k) => state.sorter.start_top_k(items, k),
+				None if state.settings.shuffle_start => {
+					// A new order every time, the log keeps the one that was used.
+					let seed = SystemTime::now()
+						.duration_since(SystemTime::UNIX_EPOCH)
+						.map_or(0, |elapsed| elapsed.as_nanos() as u64);
+					state.sorter.start_sorting_shuffled(items, seed)
+				},
+				None => state.sorter.start_sorting(items),
+			};
+			match started {
+				Ok(()) => {},
+				Err(SorterError::NothingToCompare) => continue,
+				Err(error) => {
+					state.sort_queue.clear();
+					state.status = Some(format!("Resuming, {}", error));
+					return self.settle();
+				},
+			}
+			state.sort_path = path;
+			state.last_milestone = 0;
+			return self.settle();
+		}
+
+		state.save_items();
+		self.mode = AppMode::List;
+		Command::none()
+	}
+}
+
+impl Application for App {
+	type Executor = iced::executor::Default;
+	type Flags = WindowGeometry;
+	type Message = Message;
+	type Theme = Theme;
+
+	fn new(window: WindowGeometry) -> (App, Command<Message>) {
+		let (items, mut status) = match storage::load_items() {
+			Ok(items) => (items, None),
+			Err(error) => (
+				Vec::new(),
+				Some(format!("Couldn't load the saved list: {}", error)),
+			),
+		};
+		item::claim_ids(&items);
+		// A sort from the last run only picks up again if its items are all still in the list. It
+		// may be of only some of them, like those with one tag.
+		let saved = storage::take_sort();
+		let sorter_fits = saved.as_ref().is_some_and(|sorter| {
+			let listed: HashSet<u64> = items.iter().map(|item| item.id).collect();
+			sorter.state.current_pair().is_some()
+				&& sorter
+					.current_ranking()
+					.iter()
+					.all(|item| listed.contains(&item.id))
+		});
+		let sorter = match saved {
+			Some(sorter) if sorter_fits => Some(sorter),
+			Some(_) => {
+				status.get_or_insert_with(|| {
+					"The sort from last time was dropped, some of its items are gone".into()
+				});
+				None
+			},
+			None => None,
+		};
+		let mode = if sorter.is_some() {
+			AppMode::Choose
+		} else {
+			AppMode::List
+		};
+		let state = State {
+			input_value: "".into(),
+			items,
+			sorter: sorter.unwrap_or_default(),
+			sort_path: Vec::new(),
+			sort_queue: VecDeque::new(),
+			top_k: None,
+			replay: None,
+			settings: storage::load_settings(),
+			window,
+			show_all: false,
+			show_history: false,
+			hide_completed: false,
+			dragging: None,
+			drop_target: None,
+			export_path: None,
+			status,
+			split: None,
+			ranks: Vec::new(),
+			cache: ChoiceCache::default(),
+			focused: Some(INPUT_ID.clone()),
+			tag_input: String::new(),
+			tag_filter: None,
+			search_query: String::new(),
+			last_milestone: 0,
+			pause: Pause::default(),
+			typing_ranks: false,
+			inserter: None,
+			pair_edit: None,
+			archive: Archive::default(),
+			transition: None,
+			confirm_reset: false,
+			confirm_delete_selected: false,
+			confirm_clear: false,
+			confirm_restart: false,
+			imported: None,
+			editing_template: false,
+			quit_warning: false,
+			baseline: None,
+			pending_choice: None,
+			rationale_input: String::new(),
+		};
+		(App { state, mode }, text_input::focus(INPUT_ID.clone()))
+	}
+
+	fn title(&self) -> String {
+		"Priority Sorter".into()
+	}
+
+	fn theme(&self) -> Theme {
+		self.state.settings.theme.theme()
+	}
+
+	fn update(&mut self, message: Message) -> Command<Message> {
+		let state = &mut self.state;
+		match message {
+			Message::InputChanged(value) => {
+				state.input_value = value;
+
+				Command::none()
+			},
+			Message::CreateTask => {
+				if !is_blank(&state.input_value) {
+					state.items.insert(
+						state.items.len(),
+						state.settings.new_item_template.create(&state.input_value),
+					);
+					state.input_value.clear();
+					state.ranks.clear();
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::ItemMessage(i, ItemMessage::ConfirmDelete) => {
+				state.items.remove(i);
+				state.ranks.clear();
+				state.save_items();
+
+				Command::none()
+			},
+			Message::ItemMessage(i, ItemMessage::MoveUp) => {
+				if let Some(j) = state.listed_neighbor(i, true) {
+					state.items.swap(j, i);
+					state.ranks.clear();
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::ItemMessage(i, ItemMessage::MoveDown) => {
+				if let Some(j) = state.listed_neighbor(i, false) {
+					state.items.swap(i, j);
+					state.ranks.clear();
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::DragStart(i) => {
+				state.dragging = Some(i);
+				state.drop_target = None;
+
+				Command::none()
+			},
+			Message::DragOver(i) => {
+				if state.dragging.is_some() {
+					state.drop_target = Some(i);
+				}
+
+				Command::none()
+			},
+			// Released anywhere but a row, the drag is dropped without moving anything.
+			Message::Drop => {
+				if let (Some(from), Some(to)) = (state.dragging.take(), state.drop_target.take()) {
+					if item::move_item(&mut state.items, from, to) {
+						state.ranks.clear();
+						state.save_items();
+					}
+				}
+
+				Command::none()
+			},
+			Message::ItemMessage(i, ItemMessage::AttachImage) => {
+				Command::perform(pick_image_path(), move |path| Message::ImagePicked(i, path))
+			},
+			Message::ImagePicked(i, path) => {
+				if let (Some(item), Some(path)) = (state.items.get_mut(i), path) {
+					item.image = Some(path);
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::ItemMessage(i, ItemMessage::ToggleComplete(completed)) => {
+				if i < state.items.len() {
+					if !completed {
+						state.items[i].completed = false;
+					} else if state.items[i].complete() {
+						// A recurring item starts over at the end of the list.
+						let item = state.items.remove(i);
+						state.items.push(item);
+						state.ranks.clear();
+					}
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::ItemMessage(i, item_message) => {
+				if let Some(item) = state.items.get_mut(i) {
+					let should_focus = matches!(item_message, ItemMessage::Edit);
+					let should_save = matches!(
+						item_message,
+						ItemMessage::FinishEdition | ItemMessage::ToggleSelect(_)
+					);
+					if matches!(item_message, ItemMessage::DescriptionEdited(_)) {
+						state.cache.invalidate(item.id);
+					}
+					// The question was about the selection as it was.
+					if matches!(item_message, ItemMessage::ToggleSelect(_)) {
+						state.confirm_delete_selected = false;
+					}
+
+					item.update(item_message);
+					if should_save {
+						state.save_items();
+					}
+
+					if should_focus {
+						let id = Item::text_input_id(&i);
+						state.focused = Some(id.clone());
+						Command::batch(vec![
+							text_input::focus(id.clone()),
+							text_input::select_all(id),
+						])
+					} else {
+						Command::none()
+					}
+				} else {
+					Command::none()
+				}
+			},
+			Message::FilterByTag(tag) => {
+				state.tag_filter = tag;
+
+				Command::none()
+			},
+			Message::SearchChanged(query) => {
+				state.search_query = query;
+
+				Command::none()
+			},
+			Message::TagInputChanged(value) => {
+				state.tag_input = value;
+
+				Command::none()
+			},
+			Message::TagSelected(tag) => {
+				tag_selected(&mut state.items, &tag);
+				state.tag_input.clear();
+				state.save_items();
+
+				Command::none()
+			},
+			Message::ArchiveSelected => {
+				let archived = state.archive.archive_selected(&mut state.items);
+				if archived > 0 {
+					state.ranks.clear();
+					state.status = Some(format!("Archived {} items", archived));
+				}
+				state.save_items();
+
+				Command::none()
+			},
+			Message::AskClearAll => {
+				state.confirm_clear = true;
+
+				Command::none()
+			},
+			Message::CancelClearAll => {
+				state.confirm_clear = false;
+
+				Command::none()
+			},
+			Message::ClearAll => {
+				state.confirm_clear = false;
+				state.items.clear();
+				state.reset_sort();
+				state.tag_filter = None;
+				state.search_query.clear();
+				state.status = None;
+				state.save_items();
+
+				Command::none()
+			},
+			Message::AskDeleteSelected => {
+				state.confirm_delete_selected = true;
+
+				Command::none()
+			},
+			Message::CancelDeleteSelected => {
+				state.confirm_delete_selected = false;
+
+				Command::none()
+			},
+			Message::DeleteSelected => {
+				state.confirm_delete_selected = false;
+				let deleted = item::delete_selected(&mut state.items);
+				if deleted > 0 {
+					state.ranks.clear();
+					state.status = Some(format!("Deleted {} items", deleted));
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::UndoArchive => {
+				let restored = state.archive.undo(&mut state.items);
+				state.ranks.clear();
+				state.status = Some(format!("Restored {} items", restored));
+				state.save_items();
+
+				Command::none()
+			},
+			Message::SplitItem(index) => {
+				if index < state.items.len() {
+					state.split = Some(SplitEditor {
+						index,
+						..SplitEditor::default()
+					});
+					self.mode = AppMode::Split;
+					text_input::focus(INPUT_ID.clone())
+				} else {
+					Command::none()
+				}
+			},
+			Message::SplitInputChanged(value) => {
+				if let Some(split) = &mut state.split {
+					split.input = value;
+				}
+
+				Command::none()
+			},
+			Message::SplitLineAdded => {
+				if let Some(split) = &mut state.split {
+					if !is_blank(&split.input) {
+						split.lines.push(split.input.clone());
+					}
+					split.input.clear();
+				}
+
+				Command::none()
+			},
+			Message::ConfirmSplit => {
+				if let Some(mut split) = state.split.take() {
+					split.lines.push(split.input);
+					if let Some(item) = state.items.get(split.index) {
+						let parts = item.split(&split.lines.join("\n"));
+						if !parts.is_empty() {
+							state.items.splice(split.index..=split.index, parts);
+							state.ranks.clear();
+							state.save_items();
+						}
+					}
+				}
+				self.mode = AppMode::List;
+
+				Command::none()
+			},
+			Message::TabPressed { shift } => {
+				let order = focus_order(&self.mode, &state.items);
+				match cycle_focus(&order, state.focused.as_ref(), shift) {
+					Some(id) => {
+						state.focused = Some(id.clone());
+						text_input::focus(id)
+					},
+					None => Command::none(),
+				}
+			},
+			Message::ToggleFullscreen(mode) => window::change_mode(mode),
+			Message::WindowResized(width, height) => {
+				state.window.size = (width, height);
+
+				Command::none()
+			},
+			Message::WindowMoved(x, y) => {
+				state.window.position = Some((x, y));
+
+				Command::none()
+			},
+			Message::CloseRequested => {
+				self.state.save_settings();
+				self.state.save_window();
+				if self.save_sort() {
+					window::close()
+				} else if self.should_warn_on_quit() {
+					self.state.quit_warning = true;
+					Command::none()
+				} else {
+					window::close()
+				}
+			},
+			Message::ConfirmQuit => window::close(),
+			Message::CancelQuit => {
+				state.quit_warning = false;
+
+				Command::none()
+			},
+			Message::ToggleQuitWarning(dont_show) => {
+				state.settings.warn_on_quit = !dont_show;
+				// The settings were saved before the warning showed, and quitting won't save again.
+				state.save_settings();
+
+				Command::none()
+			},
+			Message::SortItems | Message::FindTopK if state.sorter.is_done() => {
+				state.top_k = matches!(message, Message::FindTopK).then_some(TOP_K);
+				state.confirm_restart = true;
+
+				Command::none()
+			},
+			Message::SortItems | Message::FindTopK | Message::RestartSort => {
+				state.confirm_restart = false;
+				// A restart keeps what was asked for before the confirmation.
+				if !matches!(message, Message::RestartSort) {
+					state.top_k = matches!(message, Message::FindTopK).then_some(TOP_K);
+				}
+				if state.ready_to_sort() {
+					state.sort_queue = VecDeque::from([Vec::new()]);
+					state.status = None;
+					state.pause.reset();
+					self.start_next_sort()
+				} else {
+					Command::none()
+				}
+			},
+			Message::CancelRestartSort => {
+				state.confirm_restart = false;
+
+				Command::none()
+			},
+			Message::PlaceNewItems => {
+				if state.ready_to_sort() {
+					let placed: HashSet<u64> = state
+						.sorter
+						.state
+						.placed()
+						.iter()
+						.map(|item| item.id)
+						.collect();
+					let (previous, new_items) = state
+						.items
+						.iter()
+						.filter(|item| !item.completed)
+						.cloned()
+						.partition(|item| placed.contains(&item.id));
+					state.sorter.set_history_cap(state.settings.history_cap);
+					if state.sorter.start_incremental(previous, new_items).is_ok() {
+						state.sort_queue.clear();
+						state.sort_path = Vec::new();
+						state.last_milestone = 0;
+						state.status = None;
+						state.pause.reset();
+						return self.settle();
+					}
+				}
+
+				Command::none()
+			},
+			Message::SortByField { field, ascending } => {
+				field::sort_by_field(&mut state.items, field, ascending, SystemTime::now());
+				state.ranks.clear();
+				state.save_items();
+
+				Command::none()
+			},
+			Message::CheckConsistency => {
+				if state.ready_to_sort() {
+					state.baseline = Some(state.items.clone());
+					state.cache = ChoiceCache::default();
+					state.sort_queue = VecDeque::from([Vec::new()]);
+					state.status = None;
+					state.pause.reset();
+					self.start_next_sort()
+				} else {
+					Command::none()
+				}
+			},
+			Message::RefineItems => {
+				if state.ready_to_sort() {
+					state.sort_queue.clear();
+					state.sort_path.clear();
+					state.sorter.set_history_cap(state.settings.history_cap);
+					state.sorter.start_neighbor_refine(state.items.clone());
+					state.last_milestone = 0;
+					state.status = None;
+					state.pause.reset();
+					self.settle()
+				} else {
+					Command::none()
+				}
+			},
+			Message::ChooseLeft => self.choose(Choice::Left),
+			Message::ChooseRight => self.choose(Choice::Right),
+			Message::ChooseEqual => self.choose(Choice::Equal),
+			Message::Frame(now) => {
+				if let Some(transition) = &mut state.transition {
+					transition.tick(now);
+					if transition.is_finished() {
+						state.transition = None;
+					}
+				}
+
+				Command::none()
+			},
+			Message::BreakTick => {
+				state.pause.tick();
+
+				Command::none()
+			},
+			Message::ToggleInserter => {
+				state.inserter = match state.inserter {
+					Some(_) => None,
+					None => Some(Inserter::default()),
+				};
+
+				Command::none()
+			},
+			Message::InsertPositionChanged(value) => {
+				if let Some(inserter) = &mut state.inserter {
+					inserter.position = value;
+				}
+
+				Command::none()
+			},
+			Message::InsertDescriptionChanged(value) => {
+				if let Some(inserter) = &mut state.inserter {
+					inserter.description = value;
+				}
+
+				Command::none()
+			},
+			Message::EditPairItem(side) => {
+				let side = if state.settings.left_is_incoming {
+					side
+				} else {
+					side.flipped()
+				};
+				let item = state.sorter.state.current_pair().map(|(left, right)| {
+					if side == Choice::Right {
+						right
+					} else {
+						left
+					}
+				});
+				let Some(item) = item else {
+					return Command::none();
+				};
+				if item.description_locked {
+					state.status =
+						Some("This item's description is locked, unlock it first".into());
+					return Command::none();
+				}
+				state.pair_edit = Some(PairEdit {
+					id: item.id,
+					description: item.description.clone(),
+				});
+
+				text_input::focus(PAIR_EDIT_INPUT_ID.clone())
+			},
+			Message::PairEditChanged(value) => {
+				if let Some(edit) = &mut state.pair_edit {
+					edit.description = value;
+				}
+
+				Command::none()
+			},
+			Message::SavePairEdit => {
+				let Some(edit) = state.pair_edit.take_if(|edit| !is_blank(&edit.description))
+				else {
+					return Command::none();
+				};
+				let description = edit.description.trim().to_string();
+				// Only the text changes, so wherever the item is in the search it stays there.
+				// The list's copy gets it too, in case the sort is left unfinished.
+				state.sorter.edit_items(|item| {
+					if item.id == edit.id {
+						item.description.clone_from(&description);
+					}
+				});
+				if let Some(item) = item::list_at_mut(&mut state.items, &state.sort_path)
+					.and_then(|list| list.iter_mut().find(|item| item.id == edit.id))
+				{
+					item.description = description;
+				}
+				state.cache.invalidate(edit.id);
+
+				Command::none()
+			},
+			Message::CancelPairEdit => {
+				state.pair_edit = None;
+
+				Command::none()
+			},
+			Message::InsertAt(position, description) => {
+				if !is_blank(&description)
+					&& state.sorter.insert_at(position, Item::new(description))
+				{
+					state.inserter = None;
+					state.status = Some("Inserted, earlier choices can no longer be undone".into());
+				}
+
+				Command::none()
+			},
+			Message::ToggleReduceMotion(reduce) => {
+				state.settings.reduce_motion = reduce;
+				if reduce {
+					state.transition = None;
+				}
+
+				Command::none()
+			},
+			Message::ToggleAutoEqual(enabled) => {
+				state.settings.auto_equal_duplicates = enabled;
+				self.settle()
+			},
+			Message::ToggleRationale(ask) => {
+				state.settings.ask_rationale = ask;
+
+				Command::none()
+			},
+			Message::RationaleChanged(value) => {
+				state.rationale_input = value;
+
+				Command::none()
+			},
+			Message::SubmitRationale => match state.pending_choice.take() {
+				Some(choice) => {
+					let rationale = mem::take(&mut state.rationale_input);
+					let rationale = (!is_blank(&rationale)).then(|| rationale.trim().to_string());
+					self.apply_choice(choice, rationale)
+				},
+				None => Command::none(),
+			},
+			Message::SkipRationale => match state.pending_choice.take() {
+				Some(choice) => self.apply_choice(choice, None),
+				None => Command::none(),
+			},
+			Message::TagPairHigh => {
+				if !matches!(self.mode, AppMode::Choose) || state.pause.is_active() {
+					return Command::none();
+				}
+				// The sorter's copies are what the list gets back when the sort is done.
+				if let Some((left, right)) = state.sorter.state.current_pair_mut() {
+					left.add_tag(HIGH_TAG);
+					right.add_tag(HIGH_TAG);
+				}
+
+				Command::none()
+			},
+			Message::UndoChoice => {
+				// An edit is of the pair it was started on, which undoing brings back.
+				state.pair_edit = None;
+				if state.pending_choice.take().is_none() {
+					if let Err(error) = state.sorter.undo_choice() {
+						state.status = Some(format!("Can't undo, {}", error));
+					}
+				}
+
+				Command::none()
+			},
+			Message::SwapSides => {
+				state.settings.left_is_incoming = !state.settings.left_is_incoming;
+
+				Command::none()
+			},
+			Message::ListView => {
+				self.mode = AppMode::List;
+
+				Command::none()
+			},
+			Message::PauseSort => {
+				// The list shows the sorter's copies meanwhile, with any tags or fixes made to
+				// them.
+				if let Some(list) = item::list_at_mut(&mut state.items, &state.sort_path) {
+					for item in state.sorter.state.items_mut() {
+						if let Some(listed) = list.iter_mut().find(|listed| listed.id == item.id) {
+							listed.clone_from(item);
+						}
+					}
+				}
+				state.status = Some("Sort paused, resume it from here".into());
+				self.mode = AppMode::List;
+
+				Command::none()
+			},
+			Message::ResumeSort => {
+				// Edits made to the list while paused carry over. Items that are gone from it, or
+				// a list of sub-items that moved, can't be placed any more.
+				let list = item::list_at(&state.items, &state.sort_path).unwrap_or_default();
+				let mut missing = false;
+				for item in state.sorter.state.items_mut() {
+					match list.iter().find(|listed| listed.id == item.id) {
+						Some(listed) => item.clone_from(listed),
+						None => missing = true,
+					}
+				}
+				if missing {
+					state.reset_sort();
+					state.status =
+						Some("Items of the paused sort were removed, so it was dropped".into());
+					return Command::none();
+				}
+				state.status = None;
+				self.settle()
+			},
+			Message::FinishEarly => {
+				if !state.settings.allows_finish(state.sorter.comparisons()) {
+					return Command::none();
+				}
+				let result = state.sorter.take_result();
+				if !result.items.is_empty() {
+					if !result.unranked.is_empty() {
+						state.status = Some(format!(
+							"Stopped after {} choices, {} items were left unranked",
+							result.comparisons,
+							result.unranked.len()
+						));
+					}
+					if let Some(list) = item::list_at_mut(&mut state.items, &state.sort_path) {
+						let mut ranked = result.items;
+						ranked.extend(result.unranked);
+						item::splice_ranked(list, ranked);
+					}
+					state.save_items();
+				}
+				state.sort_queue.clear();
+				state.pending_choice = None;
+				state.inserter = None;
+				state.pair_edit = None;
+				state.baseline = None;
+				state.ranks.clear();
+				self.mode = AppMode::List;
+
+				Command::none()
+			},
+			Message::QuickExport => {
+				if !self.can_quick_export() {
+					return Command::none();
+				}
+				match self.state.export_path.clone() {
+					Some(path) => {
+						self.export_to(path);
+						Command::none()
+					},
+					None => Command::perform(
+						pick_save_path(self.state.settings.export_format.file_name()),
+						Message::ExportTo,
+					),
+				}
+			},
+			Message::ExportTo(path) => {
+				if let Some(path) = path {
+					self.export_to(path);
+				}
+
+				Command::none()
+			},
+			Message::ExportFormatSelected(format) => {
+				if state.settings.export_format != format {
+					state.settings.export_format = format;
+					// The file picked for the previous format has the wrong extension now.
+					state.export_path = None;
+				}
+
+				Command::none()
+			},
+			Message::SaveSession => {
+				Command::perform(pick_save_path("session.json"), Message::SaveSessionTo)
+			},
+			Message::SaveSessionTo(path) => {
+				if let Some(path) = path {
+					self.save_session(path);
+				}
+
+				Command::none()
+			},
+			Message::OpenSession => Command::perform(pick_open_path(), Message::SessionPicked),
+			Message::SessionPicked(path) => match path {
+				Some(path) => self.open_session(path),
+				None => Command::none(),
+			},
+			Message::ReplaySession => {
+				Command::perform(pick_open_path(), Message::ReplaySessionPicked)
+			},
+			Message::ReplaySessionPicked(path) => {
+				if let Some(path) = path {
+					self.replay_session(path);
+				}
+
+				Command::none()
+			},
+			Message::ImportFile => Command::perform(pick_text_path(), Message::ImportPicked),
+			Message::ImportPicked(path) => {
+				if let Some(path) = path {
+					self.import_file(path);
+				}
+
+				Command::none()
+			},
+			Message::ExportJson => {
+				Command::perform(pick_save_path("items.json"), Message::ExportJsonTo)
+			},
+			Message::ExportJsonTo(path) => {
+				if let Some(path) = path {
+					self.export_json(path);
+				}
+
+				Command::none()
+			},
+			Message::ImportJson => Command::perform(pick_json_path(), Message::ImportJsonPicked),
+			Message::ImportJsonPicked(path) => {
+				if let Some(path) = path {
+					self.import_json(path);
+				}
+
+				Command::none()
+			},
+			Message::AddImported => {
+				if let Some(mut imported) = state.imported.take() {
+					item::renew_ids(&mut imported);
+					let count = imported.len();
+					state.items.extend(imported);
+					state.ranks.clear();
+					state.status = Some(format!("Added {} items", count));
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::ReplaceWithImported => {
+				if let Some(imported) = state.imported.take() {
+					item::claim_ids(&imported);
+					state.items = imported;
+					state.reset_sort();
+					state.tag_filter = None;
+					state.search_query.clear();
+					state.status = None;
+					state.save_items();
+				}
+
+				Command::none()
+			},
+			Message::CancelImport => {
+				state.imported = None;
+
+				Command::none()
+			},
+			Message::PasteItems => clipboard::read(Message::ItemsPasted),
+			Message::ItemsPasted(text) => match text {
+				Some(text) if text.lines().filter(|line| !is_blank(line)).count() > 1 => {
+					let (added, skipped) = state.add_lines(&text);
+					state.status = Some(format!(
+						"Pasted {} items, skipped {} already in the list",
+						added, skipped
+					));
+
+					Command::none()
+				},
+				// A single line goes into the input like any paste, to be edited before adding.
+				Some(text) => {
+					state.input_value.push_str(text.trim());
+					state.focused = Some(INPUT_ID.clone());
+					text_input::focus(INPUT_ID.clone())
+				},
+				None => {
+					state.status = Some("There is no text on the clipboard".into());
+
+					Command::none()
+				},
+			},
+			Message::ToggleTypedRanks => {
+				state.typing_ranks = !state.typing_ranks;
+				state.items.iter_mut().for_each(Item::clear_typed_rank);
+
+				Command::none()
+			},
+			Message::ApplyTypedRanks => {
+				let typed: Vec<&str> = state.items.iter().map(Item::typed_rank).collect();
+				let typed = typed_ranks::typed_order(&typed);
+				let mut problems = Vec::new();
+				if !typed.duplicates.is_empty() {
+					let ranks: Vec<String> =
+						typed.duplicates.iter().map(usize::to_string).collect();
+					problems.push(format!(
+						"rank {} given more than once, kept in list order",
+						ranks.join(", ")
+					));
+				}
+				if !typed.invalid.is_empty() {
+					problems.push(format!(
+						"{} entries were not positive numbers and were ignored",
+						typed.invalid.len()
+					));
+				}
+				state.status = (!problems.is_empty()).then(|| problems.join("; "));
+
+				let mut items: Vec<Option<Item>> = state.items.drain(..).map(Some).collect();
+				state.items = typed
+					.order
+					.iter()
+					.filter_map(|&i| items[i].take())
+					.collect();
+				state.items.iter_mut().for_each(Item::clear_typed_rank);
+				state.typing_ranks = false;
+				state.ranks.clear();
+				state.save_items();
+
+				Command::none()
+			},
+			Message::ToggleBalancedSeed(balanced) => {
+				state.settings.seed_strategy = if balanced {
+					SeedStrategy::Balanced
+				} else {
+					SeedStrategy::Decayed
+				};
+
+				Command::none()
+			},
+			Message::ToggleAlternateEnds(alternate) => {
+				state.settings.alternate_ends = alternate;
+
+				Command::none()
+			},
+			Message::ToggleShuffleStart(shuffle) => {
+				state.settings.shuffle_start = shuffle;
+
+				Command::none()
+			},
+			Message::ToggleMergeInsertion(merge) => {
+				state.settings.sort_strategy = if merge {
+					SortStrategy::MergeInsertion
+				} else {
+					SortStrategy::BinaryInsertion
+				};
+
+				Command::none()
+			},
+			Message::ToggleTemplate => {
+				state.editing_template = !state.editing_template;
+
+				Command::none()
+			},
+			Message::TemplatePrefixChanged(prefix) => {
+				state.settings.new_item_template.prefix = prefix;
+
+				Command::none()
+			},
+			Message::TemplateTagsChanged(tags) => {
+				state.settings.new_item_template.tags = tags;
+
+				Command::none()
+			},
+			Message::TemplateNotesChanged(notes) => {
+				state.settings.new_item_template.notes = notes;
+
+				Command::none()
+			},
+			Message::AskResetSettings => {
+				state.confirm_reset = true;
+
+				Command::none()
+			},
+			Message::CancelResetSettings => {
+				state.confirm_reset = false;
+
+				Command::none()
+			},
+			Message::ResetSettings => {
+				state.settings = Settings::default();
+				state.save_settings();
+				state.confirm_reset = false;
+				state.status = Some("Settings reset to their defaults".into());
+
+				Command::none()
+			},
+			Message::ToggleCounts(show) => {
+				state.settings.show_counts = show;
+
+				Command::none()
+			},
+			Message::ToggleIndices(show) => {
+				state.settings.show_indices = show;
+
+				Command::none()
+			},
+			Message::ToggleRightToLeft(right_to_left) => {
+				state.settings.right_to_left = right_to_left;
+
+				Command::none()
+			},
+			Message::ToggleDensity => {
+				state.settings.density = state.settings.density.toggled();
+
+				Command::none()
+			},
+			Message::ToggleTheme => {
+				state.settings.theme = state.settings.theme.toggled();
+				state.save_settings();
+
+				Command::none()
+			},
+			Message::ToggleShowAll => {
+				state.show_all = !state.show_all;
+
+				Command::none()
+			},
+			Message::CopyTranscript => match state.sorter.log() {
+				Some(log) => {
+					state.status = Some("Transcript copied to the clipboard".into());
+					clipboard::write(export::transcript(log))
+				},
+				None => Command::none(),
+			},
+			Message::ToggleHistory => {
+				state.show_history = !state.show_history;
+
+				Command::none()
+			},
+			Message::ToggleShowCompleted => {
+				state.hide_completed = !state.hide_completed;
+
+				Command::none()
+			},
+			Message::CopyHistory => {
+				state.status = Some("Comparisons copied to the clipboard".into());
+				clipboard::write(export::comparison_history(&state.sorter.history()))
+			},
+			Message::CopyMarkdown => {
+				state.status = Some("Ranking copied to the clipboard as Markdown".into());
+				clipboard::write(export::export_markdown(&state.items))
+			},
+			Message::CopyCsv => {
+				state.status = Some("Ranking copied to the clipboard as CSV".into());
+				clipboard::write(export::export_csv(&state.items))
+			},
+			Message::CopyOutline => {
+				state.status = Some("Outline copied to the clipboard".into());
+				clipboard::write(export::items_to_outline(&state.items))
+			},
+			Message::CopyAbSummary => match state.sorter.log() {
+				Some(log) => {
+					state.status = Some("A/B summary copied to the clipboard".into());
+					clipboard::write(export::ab_summary(log, AB_GROUPS.0, AB_GROUPS.1))
+				},
+				None => Command::none(),
+			},
+			Message::ReplayLastSort => {
+				if let Some(log) = state.sorter.log() {
+					state.replay = Some(Replay::new(log.clone()));
+					self.mode = AppMode::Replay;
+				}
+
+				Command::none()
+			},
+			Message::ReplayForward => {
+				if let Some(replay) = &mut state.replay {
+					replay.forward();
+				}
+
+				Command::none()
+			},
+			Message::ReplayBack => {
+				if let Some(replay) = &mut state.replay {
+					replay.back();
+				}
+
+				Command::none()
+			},
+		}
+	}
+
+	fn view(&self) -> Element<Message> {
+		let content = match self.mode {
+			_ if self.state.quit_warning => self.quit_warning_view(),
+			AppMode::List => self.list_view(),
+			AppMode::Choose => self.choose_view(),
+			AppMode::Replay => self.replay_view(),
+			AppMode::Split => self.split_view(),
+		};
+
+		scrollable(
+			container(content)
+				.width(Length::Fill)
+				.padding(40)
+				.center_x(),
+		)
+		.into()
+	}
+
+	fn subscription(&self) -> Subscription<Message> {
+		let keys = subscription::events_with(app_keys);
+
+		let mut subscriptions = vec![keys];
+		// Keys that only mean something for one mode are only listened to in it, so the arrows
+		// still move the cursor of the list's text inputs. A focused input captures them in any
+		// mode, like the rationale one while choosing.
+		match self.mode {
+			AppMode::Choose => subscriptions.push(if self.state.settings.right_to_left {
+				subscription::events_with(|event, status| choose_keys(event, status, true))
+			} else {
+				subscription::events_with(|event, status| choose_keys(event, status, false))
+			}),
+			AppMode::List | AppMode::Replay | AppMode::Split => {},
+		}
+		// The row a drag is released over says so first, as widgets are heard before this.
+		if self.state.dragging.is_some() {
+			subscriptions.push(subscription::events_with(|event, _| match event {
+				Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+					Some(Message::Drop)
+				},
+				_ => None,
+			}));
+		}
+		if self.state.pause.is_active() {
+			subscriptions.push(time::every(Duration::from_secs(1)).map(|_| Message::BreakTick));
+		}
+		if self.state.transition.is_some() {
+			subscriptions.push(window::frames().map(Message::Frame));
+		}
+		Subscription::batch(subscriptions)
+	}
+}
+
+/// The shortcuts and window events listened to in every mode.
+fn app_keys(event: Event, status: event::Status) -> Option<Message> {
+	match (event, status) {
+		(
+			Event::Keyboard(keyboard::Event::KeyPressed {
+				key_code: keyboard::KeyCode::Tab,
+				modifiers,
+				..
+			}),
+			event::Status::Ignored,
+		) => Some(Message::TabPressed {
+			shift: modifiers.shift(),
+		}),
+		(
+			Event::Keyboard(keyboard::Event::KeyPressed {
+				key_code,
+				modifiers: Modifiers::SHIFT,
+			}),
+			event::Status::Ignored,
+		) => match key_code {
+			KeyCode::Up => Some(Message::ToggleFullscreen(window::Mode::Fullscreen)),
+			KeyCode::Down => Some(Message::ToggleFullscreen(window::Mode::Windowed)),
+			_ => None,
+		},
+		(
+			Event::Keyboard(keyboard::Event::KeyPressed {
+				key_code: KeyCode::E,
+				modifiers: Modifiers::CTRL,
+			}),
+			event::Status::Ignored,
+		) => Some(Message::QuickExport),
+		(Event::Window(window::Event::CloseRequested), _) => Some(Message::CloseRequested),
+		(Event::Window(window::Event::Resized { width, height }), _) => {
+			Some(Message::WindowResized(width, height))
+		},
+		(Event::Window(window::Event::Moved { x, y }), _) => Some(Message::WindowMoved(x, y)),
+		_ => None,
+	}
+}
+
+/// The shortcuts of the choose screen. A plain Left or Right arrow picks the item on that side,
+/// with the sides swapped when the row is `mirrored`, and Shift+Space tags both items.
+fn choose_keys(event: Event, status: event::Status, mirrored: bool) -> Option<Message> {
+	match (event, status) {
+		(
+			Event::Keyboard(keyboard::Event::KeyPressed {
+				key_code,
+				modifiers,
+			}),
+			event::Status::Ignored,
+		) if modifiers.is_empty() => match key_code {
+			KeyCode::Left if mirrored => Some(Message::ChooseRight),
+			KeyCode::Right if mirrored => Some(Message::ChooseLeft),
+			KeyCode::Left => Some(Message::ChooseLeft),
+			KeyCode::Right => Some(Message::ChooseRight),
+			_ => None,
+		},
+		(
+			Event::Keyboard(keyboard::Event::KeyPressed {
+				key_code: KeyCode::Space,
+				modifiers: Modifiers::SHIFT,
+			}),
+			event::Status::Ignored,
+		) => Some(Message::TagPairHigh),
+		_ => None,
+	}
+}
+
+async fn pick_save_path(file_name: &str) -> Option<PathBuf> {
+	rfd::AsyncFileDialog::new()
+		.set_file_name(file_name)
+		.save_file()
+		.await
+		.map(|file| file.path().to_path_buf())
+}
+
+async fn pick_image_path() -> Option<PathBuf> {
+	rfd::AsyncFileDialog::new()
+		.add_filter("Images", &["png", "jpg", "jpeg", "gif", "bmp", "webp"])
+		.pick_file()
+		.await
+		.map(|file| file.path().to_path_buf())
+}
+
+async fn pick_text_path() -> Option<PathBuf> {
+	rfd::AsyncFileDialog::new()
+		.add_filter("Text", &["txt"])
+		.pick_file()
+		.await
+		.map(|file| file.path().to_path_buf())
+}
+
+async fn pick_json_path() -> Option<PathBuf> {
+	rfd::AsyncFileDialog::new()
+		.add_filter("JSON", &["json"])
+		.pick_file()
+		.await
+		.map(|file| file.path().to_path_buf())
+}
+
+async fn pick_open_path() -> Option<PathBuf> {
+	rfd::AsyncFileDialog::new()
+		.pick_file()
+		.await
+		.map(|file| file.path().to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn a_short_list_is_not_collapsed() {
+		assert_eq!(
+			collapsed_rows(4, 2, false),
+			(0..4).map(ListRow::Item).collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn collapsed_rows_keep_the_indices_past_the_elided_middle() {
+		assert_eq!(
+			collapsed_rows(10, 2, false),
+			vec![
+				ListRow::Item(0),
+				ListRow::Item(1),
+				ListRow::Elided(6),
+				ListRow::Item(8),
+				ListRow::Item(9),
+			]
+		);
+	}
+
+	#[test]
+	fn showing_all_lists_every_row() {
+		assert_eq!(collapsed_rows(10, 2, true).len(), 10);
+	}
+
+	#[test]
+	fn tab_in_the_list_goes_through_the_input_and_the_rows_being_edited() {
+		let mut items: Vec<Item> = ["a", "b", "c"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		items[2].update(ItemMessage::Edit);
+		let order = focus_order(&AppMode::List, &items);
+		assert_eq!(order, vec![INPUT_ID.clone(), Item::text_input_id(&2)]);
+
+		assert_eq!(cycle_focus(&order, None, false), Some(INPUT_ID.clone()));
+		assert_eq!(
+			cycle_focus(&order, Some(&INPUT_ID), false),
+			Some(Item::text_input_id(&2))
+		);
+		assert_eq!(
+			cycle_focus(&order, Some(&Item::text_input_id(&2)), false),
+			Some(INPUT_ID.clone())
+		);
+		assert_eq!(
+			cycle_focus(&order, Some(&INPUT_ID), true),
+			Some(Item::text_input_id(&2))
+		);
+	}
+
+	#[test]
+	fn tab_while_choosing_focuses_nothing() {
+		let items = vec![Item::new("a".into())];
+		let order = focus_order(&AppMode::Choose, &items);
+		assert!(order.is_empty());
+		assert_eq!(cycle_focus(&order, None, false), None);
+		assert_eq!(cycle_focus(&order, None, true), None);
+	}
+
+	#[test]
+	fn blank_lines_arent_added() {
+		let mut state = State::default();
+		assert_eq!(state.add_lines("   \n\t\n"), (0, 0));
+		assert!(state.items.is_empty());
+	}
+
+	#[test]
+	fn duplicates_are_marked_equal_without_asking() {
+		let mut state = State::default();
+		state.settings.auto_equal_duplicates = true;
+		let items = ["Call mum", "Pay rent", "call  Mum"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		state.sorter.start_sorting(items).unwrap();
+		while state.sorter.state.current_pair().is_some() {
+			state.skip_known();
+			if let Some((left, right)) = state.sorter.state.current_pair() {
+				assert_ne!(
+					item::normalized(&left.description),
+					item::normalized(&right.description),
+					"asked about duplicates"
+				);
+				state.sorter.make_choice(Choice::Left).unwrap();
+			}
+		}
+		let log = state.sorter.log().unwrap();
+		assert!(log
+			.choices
+			.iter()
+			.any(|decision| decision.choice == Choice::Equal));
+	}
+
+	#[test]
+	fn rows_are_numbered_by_rank_until_the_list_changes() {
+		let mut state = State {
+			items: ["a", "b", "c", "d"]
+				.iter()
+				.map(|description| Item::new((*description).into()))
+				.collect(),
+			ranks: vec![(1, 1.0), (1, 1.0), (3, 0.5), (4, 0.0)],
+			..State::default()
+		};
+		assert_eq!(state.number(1), (1, Some(1.0)));
+
+		// Even ranks not cleared yet no longer apply once an item is gone.
+		state.items[0].selected = true;
+		item::delete_selected(&mut state.items);
+		let numbers: Vec<_> = (0..3).map(|i| state.number(i)).collect();
+		assert_eq!(numbers, [(1, None), (2, None), (3, None)]);
+
+		state.ranks.clear();
+		item::move_item(&mut state.items, 0, 2);
+		assert_eq!(state.items[2].description, "b");
+		assert_eq!(state.number(2), (3, None));
+	}
+
+	#[test]
+	fn quick_export_waits_for_a_finished_sort() {
+		let mut app = App {
+			state: State::default(),
+			mode: AppMode::List,
+		};
+		assert!(!app.can_quick_export());
+
+		let items = ["a", "b"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		app.state.sorter.start_sorting(items).unwrap();
+		app.mode = AppMode::Choose;
+		assert!(!app.can_quick_export());
+
+		app.state.sorter.make_choice(Choice::Left).unwrap();
+		app.mode = AppMode::List;
+		assert!(app.can_quick_export());
+	}
+
+	#[test]
+	fn an_empty_list_shows_the_empty_state() {
+		let mut state = State::default();
+		assert_eq!(state.list_content(), ListContent::Empty);
+
+		state.items = vec![Item::new("Answer emails".into())];
+		assert_eq!(state.list_content(), ListContent::Rows(vec![0]));
+		state.search_query = "trip".into();
+		assert_eq!(
+			state.list_content(),
+			ListContent::NothingListed("No items match the search.")
+		);
+	}
+
+	#[test]
+	fn the_quit_warning_only_shows_mid_sort_while_turned_on() {
+		let mut app = App {
+			state: State::default(),
+			mode: AppMode::List,
+		};
+		app.state.settings.warn_on_quit = true;
+		assert!(!app.should_warn_on_quit());
+
+		let items = ["a", "b"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		app.state.sorter.start_sorting(items).unwrap();
+		app.mode = AppMode::Choose;
+		assert!(app.should_warn_on_quit());
+
+		app.state.settings.warn_on_quit = false;
+		assert!(!app.should_warn_on_quit());
+	}
+
+	#[test]
+	fn reduced_motion_shows_a_new_pair_without_sliding() {
+		for reduce_motion in [false, true] {
+			let mut app = App {
+				state: State::default(),
+				mode: AppMode::List,
+			};
+			app.state.settings.reduce_motion = reduce_motion;
+			let items = ["a", "b"]
+				.iter()
+				.map(|description| Item::new((*description).into()))
+				.collect();
+			app.state.sorter.start_sorting(items).unwrap();
+			let _ = app.settle();
+			assert!(matches!(app.mode, AppMode::Choose));
+			assert_eq!(app.state.transition.is_some(), !reduce_motion);
+
+			let _ = app.update(Message::ToggleReduceMotion(true));
+			assert!(app.state.transition.is_none());
+		}
+	}
+
+	fn key_press(key_code: KeyCode) -> Event {
+		Event::Keyboard(keyboard::Event::KeyPressed {
+			key_code,
+			modifiers: Modifiers::empty(),
+		})
+	}
+
+	#[test]
+	fn mirrored_arrows_pick_the_item_on_their_side() {
+		let choose =
+			|key_code, mirrored| choose_keys(key_press(key_code), event::Status::Ignored, mirrored);
+		assert!(matches!(
+			choose(KeyCode::Left, false),
+			Some(Message::ChooseLeft)
+		));
+		assert!(matches!(
+			choose(KeyCode::Right, false),
+			Some(Message::ChooseRight)
+		));
+		// Mirrored, the item a left choice picks is shown on the right.
+		assert!(matches!(
+			choose(KeyCode::Left, true),
+			Some(Message::ChooseRight)
+		));
+		assert!(matches!(
+			choose(KeyCode::Right, true),
+			Some(Message::ChooseLeft)
+		));
+		assert!(choose_keys(key_press(KeyCode::Left), event::Status::Captured, true).is_none());
+	}
+
+	#[test]
+	fn tagging_the_pair_high_still_needs_a_choice() {
+		let mut app = App {
+			state: State::default(),
+			mode: AppMode::Choose,
+		};
+		let items = ["a", "b", "c"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		app.state.sorter.start_sorting(items).unwrap();
+		let _ = app.update(Message::TagPairHigh);
+
+		let (left, right) = app.state.sorter.state.current_pair().unwrap();
+		assert!(left.has_tag(HIGH_TAG) && right.has_tag(HIGH_TAG));
+		assert_eq!(app.state.sorter.comparisons(), 0);
+		assert!(!app.state.sorter.state.waiting()[0].has_tag(HIGH_TAG));
+	}
+
+	#[test]
+	fn choosing_shortcuts_arent_heard_outside_the_choose_screen() {
+		for key_code in [KeyCode::Left, KeyCode::Right] {
+			assert!(app_keys(key_press(key_code), event::Status::Ignored).is_none());
+		}
+		let tag_high = Event::Keyboard(keyboard::Event::KeyPressed {
+			key_code: KeyCode::Space,
+			modifiers: Modifiers::SHIFT,
+		});
+		assert!(app_keys(tag_high.clone(), event::Status::Ignored).is_none());
+		assert!(matches!(
+			choose_keys(tag_high, event::Status::Ignored, false),
+			Some(Message::TagPairHigh)
+		));
+	}
+
+	#[test]
+	fn the_choose_screen_isnt_kept_without_a_question() {
+		let mut app = App {
+			state: State::default(),
+			mode: AppMode::Choose,
+		};
+		let _ = app.settle();
+		assert!(matches!(app.mode, AppMode::List));
 	}
 }
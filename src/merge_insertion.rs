@@ -0,0 +1,202 @@
+use std::{collections::HashMap, slice};
+
+use crate::sorter::Choice;
+
+/// Where a merge-insertion sort of some items stands after a number of choices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Replayed {
+	/// The next question, as the indices of the left and the right item.
+	Ask(usize, usize),
+	/// The indices from highest to lowest, with `tied[i]` set when item `i` was declared equal to
+	/// item `i - 1`.
+	Sorted { order: Vec<usize>, tied: Vec<bool> },
+}
+
+/// Runs Ford–Johnson merge-insertion over `len` items from the start, answering its questions
+/// with `choices` in turn, until it finishes or runs out of answers.
+///
+/// Going through the questions again is cheap next to asking them, and means nothing but the
+/// choices has to be kept.
+///
+/// A choice of `Equal` counts as the left item ranking at least as high as the right one.
+pub fn replay(len: usize, choices: &[Choice]) -> Replayed {
+	let mut answers = Answers {
+		choices: choices.iter(),
+		ties: Vec::new(),
+	};
+	match sort((0..len).collect(), &mut answers) {
+		Err((left, right)) => Replayed::Ask(left, right),
+		Ok(mut order) => {
+			order.reverse();
+			let tied = (0..order.len())
+				.map(|i| {
+					i > 0 && {
+						let pair = (order[i - 1], order[i]);
+						answers.ties.contains(&pair) || answers.ties.contains(&(pair.1, pair.0))
+					}
+				})
+				.collect();
+			Replayed::Sorted { order, tied }
+		},
+	}
+}
+
+/// The most questions merge-insertion can take to sort `len` items, which is the sum of
+/// `ceil(log2(3k / 4))` for `k` from 1 to `len`.
+pub fn max_comparisons(len: usize) -> usize {
+	(1..=len)
+		.map(|k| {
+			let mut questions = 0;
+			while 4 << questions < 3 * k {
+				questions += 1;
+			}
+			questions
+		})
+		.sum()
+}
+
+struct Answers<'a> {
+	choices: slice::Iter<'a, Choice>,
+	/// The pairs declared equal so far, left first.
+	ties: Vec<(usize, usize)>,
+}
+
+impl Answers<'_> {
+	/// Whether item `left` ranks below item `right`, or the question if it hasn't been answered.
+	fn below(&mut self, left: usize, right: usize) -> Result<bool, (usize, usize)> {
+		match self.choices.next().ok_or((left, right))? {
+			Choice::Left => Ok(false),
+			Choice::Right => Ok(true),
+			Choice::Equal => {
+				self.ties.push((left, right));
+				Ok(false)
+			},
+		}
+	}
+}
+
+/// Sorts `items` lowest first.
+fn sort(items: Vec<usize>, answers: &mut Answers) -> Result<Vec<usize>, (usize, usize)> {
+	if items.len() < 2 {
+		return Ok(items);
+	}
+
+	// Each pair's winner goes on to be sorted, and its loser is known to rank below it.
+	let mut losers = HashMap::new();
+	let mut winners = Vec::new();
+	for pair in items.chunks_exact(2) {
+		let (loser, winner) = if answers.below(pair[0], pair[1])? {
+			(pair[0], pair[1])
+		} else {
+			(pair[1], pair[0])
+		};
+		losers.insert(winner, loser);
+		winners.push(winner);
+	}
+	let mut chain = sort(winners, answers)?;
+
+	// The losers in the order of their winners, each with the winner it has to end up below. An
+	// item left over from the pairing has none.
+	let pending: Vec<(usize, Option<usize>)> = chain
+		.iter()
+		.map(|winner| (losers[winner], Some(*winner)))
+		.chain((items.len() % 2 == 1).then(|| (items[items.len() - 1], None)))
+		.collect();
+	// The lowest winner's loser goes below the whole chain without a question.
+	chain.insert(0, pending[0].0);
+
+	// Losers are inserted in groups ending at the Jacobsthal numbers 3, 5, 11, 21, …, each group
+	// from its end back, so every search is over a chain of just under a power of two.
+	let (mut previous, mut last) = (1, 1);
+	while last < pending.len() {
+		let end = (last + 2 * previous).min(pending.len());
+		for &(loser, winner) in pending[last..end].iter().rev() {
+			let (mut lo, mut hi) = (
+				0,
+				winner
+					.and_then(|winner| chain.iter().position(|&item| item == winner))
+					.unwrap_or(chain.len()),
+			);
+			while lo < hi {
+				let mid = lo + (hi - lo) / 2;
+				if answers.below(loser, chain[mid])? {
+					hi = mid;
+				} else {
+					lo = mid + 1;
+				}
+			}
+			chain.insert(lo, loser);
+		}
+		(previous, last) = (last, last + 2 * previous);
+	}
+	Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Sorts `values` highest first, answering every question truthfully, and returns the order
+	/// and how many questions it took.
+	fn sort_values(values: &[u32]) -> (Vec<u32>, usize) {
+		let mut choices = Vec::new();
+		loop {
+			match replay(values.len(), &choices) {
+				Replayed::Ask(left, right) => choices.push(if values[left] >= values[right] {
+					Choice::Left
+				} else {
+					Choice::Right
+				}),
+				Replayed::Sorted { order, .. } => {
+					return (order.iter().map(|&i| values[i]).collect(), choices.len());
+				},
+			}
+		}
+	}
+
+	fn permutations(values: &[u32]) -> Vec<Vec<u32>> {
+		if values.len() <= 1 {
+			return vec![values.to_vec()];
+		}
+		let mut all = Vec::new();
+		for i in 0..values.len() {
+			let mut rest = values.to_vec();
+			let first = rest.remove(i);
+			for mut permutation in permutations(&rest) {
+				permutation.insert(0, first);
+				all.push(permutation);
+			}
+		}
+		all
+	}
+
+	#[test]
+	fn the_bound_matches_the_known_values() {
+		let known = [0, 0, 1, 3, 5, 7, 10, 13, 16, 19, 22, 26, 30];
+		for (len, &bound) in known.iter().enumerate() {
+			assert_eq!(max_comparisons(len), bound, "for {} items", len);
+		}
+	}
+
+	#[test]
+	fn every_order_is_sorted_within_the_bound() {
+		for len in 0..=7 {
+			let values: Vec<u32> = (0..len).collect();
+			let mut expected = values.clone();
+			expected.reverse();
+			for permutation in permutations(&values) {
+				let (order, questions) = sort_values(&permutation);
+				assert_eq!(order, expected, "for {:?}", permutation);
+				assert!(questions <= max_comparisons(len as usize));
+			}
+		}
+	}
+
+	#[test]
+	fn equal_answers_are_reported_as_ties() {
+		let Replayed::Sorted { tied, .. } = replay(2, &[Choice::Equal]) else {
+			panic!("two items take one question");
+		};
+		assert_eq!(tied, [false, true]);
+	}
+}
@@ -0,0 +1,68 @@
+const MILESTONES: [u8; 4] = [25, 50, 75, 100];
+
+/// The highest milestone, in percent, that `completion` reaches beyond `last`, so each one is
+/// only celebrated once.
+pub fn crossed(last: u8, completion: f32) -> Option<u8> {
+	MILESTONES
+		.into_iter()
+		.rev()
+		.find(|&milestone| milestone > last && completion * 100.0 >= f32::from(milestone))
+}
+
+pub fn message(milestone: u8) -> String {
+	if milestone >= 100 {
+		"All done, nice work!".into()
+	} else {
+		format!("{}% of the way there!", milestone)
+	}
+}
+
+#[cfg(feature = "chime")]
+pub fn chime() {
+	use std::{thread, time::Duration};
+
+	use rodio::{source::SineWave, OutputStream, Sink, Source};
+
+	thread::spawn(|| {
+		let Ok((_stream, handle)) = OutputStream::try_default() else {
+			return;
+		};
+		let Ok(sink) = Sink::try_new(&handle) else {
+			return;
+		};
+		sink.append(
+			SineWave::new(880.0)
+				.take_duration(Duration::from_millis(150))
+				.amplify(0.2),
+		);
+		sink.sleep_until_end();
+	});
+}
+
+#[cfg(not(feature = "chime"))]
+pub const fn chime() {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn nothing_is_crossed_before_the_first_quarter() {
+		assert_eq!(crossed(0, 0.0), None);
+		assert_eq!(crossed(0, 0.24), None);
+	}
+
+	#[test]
+	fn each_milestone_is_crossed_once() {
+		assert_eq!(crossed(0, 0.25), Some(25));
+		assert_eq!(crossed(25, 0.3), None);
+		assert_eq!(crossed(25, 0.5), Some(50));
+		assert_eq!(crossed(100, 1.0), None);
+	}
+
+	#[test]
+	fn a_jump_reports_only_the_highest_milestone() {
+		assert_eq!(crossed(0, 0.8), Some(75));
+		assert_eq!(crossed(25, 1.0), Some(100));
+	}
+}
@@ -0,0 +1,75 @@
+/// Enforces a short break after a run of choices, to keep decisions fresh on long sorts.
+#[derive(Debug, Default)]
+pub struct Pause {
+	streak: usize,
+	remaining: u32,
+}
+
+impl Pause {
+	pub fn reset(&mut self) {
+		*self = Self::default();
+	}
+
+	/// Counts a choice, starting a break of `seconds` once `every` choices have been made in a
+	/// row. Does nothing when breaks are turned off.
+	pub fn record_choice(&mut self, every: Option<usize>, seconds: u32) {
+		let Some(every) = every.filter(|&every| every > 0) else {
+			return;
+		};
+		self.streak += 1;
+		if self.streak >= every {
+			self.streak = 0;
+			self.remaining = seconds;
+		}
+	}
+
+	pub fn tick(&mut self) {
+		self.remaining = self.remaining.saturating_sub(1);
+	}
+
+	pub const fn remaining(&self) -> u32 {
+		self.remaining
+	}
+
+	pub const fn is_active(&self) -> bool {
+		self.remaining > 0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn a_break_starts_after_every_choices() {
+		let mut pause = Pause::default();
+		pause.record_choice(Some(3), 5);
+		pause.record_choice(Some(3), 5);
+		assert!(!pause.is_active());
+		pause.record_choice(Some(3), 5);
+		assert!(pause.is_active());
+		assert_eq!(pause.remaining(), 5);
+	}
+
+	#[test]
+	fn choices_come_back_when_the_countdown_ends() {
+		let mut pause = Pause::default();
+		pause.record_choice(Some(1), 2);
+		pause.tick();
+		assert!(pause.is_active());
+		pause.tick();
+		assert!(!pause.is_active());
+		pause.tick();
+		assert_eq!(pause.remaining(), 0);
+	}
+
+	#[test]
+	fn no_break_when_turned_off() {
+		let mut pause = Pause::default();
+		for _ in 0..100 {
+			pause.record_choice(None, 5);
+			pause.record_choice(Some(0), 5);
+		}
+		assert!(!pause.is_active());
+	}
+}
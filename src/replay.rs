@@ -0,0 +1,111 @@
+use crate::sorter::{Choice, DecisionLog, Identify, SortState, Sorter};
+
+/// Steps through a recorded session without asking anything, so the order can be watched as it
+/// was built.
+#[derive(Debug)]
+pub struct Replay<T> {
+	log: DecisionLog<T>,
+	sorter: Sorter<T>,
+	step: usize,
+}
+
+impl<T: Clone + Identify> Replay<T> {
+	pub fn new(log: DecisionLog<T>) -> Self {
+		let sorter = Sorter::at_start(&log);
+		Self {
+			log,
+			sorter,
+			step: 0,
+		}
+	}
+
+	pub const fn step(&self) -> usize {
+		self.step
+	}
+
+	pub const fn total_steps(&self) -> usize {
+		self.log.choices.len()
+	}
+
+	pub const fn is_finished(&self) -> bool {
+		self.step == self.total_steps()
+	}
+
+	pub const fn state(&self) -> &SortState<T> {
+		&self.sorter.state
+	}
+
+	/// The choice that was made for the pair currently shown.
+	pub fn next_choice(&self) -> Option<Choice> {
+		self.log
+			.choices
+			.get(self.step)
+			.map(|decision| decision.choice)
+	}
+
+	/// The reason given for the choice made for the pair currently shown.
+	pub fn next_rationale(&self) -> Option<&str> {
+		self.log.choices.get(self.step)?.rationale.as_deref()
+	}
+
+	pub fn forward(&mut self) {
+		if let Some(choice) = self.next_choice() {
+			if self.sorter.make_choice(choice).is_ok() {
+				self.step += 1;
+			}
+		}
+	}
+
+	pub fn back(&mut self) {
+		let target = self.step.saturating_sub(1);
+		self.sorter = Sorter::at_start(&self.log);
+		self.step = 0;
+		while self.step < target {
+			self.forward();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn answer(left: &u32, right: &u32) -> Option<Choice> {
+		Some(if left > right {
+			Choice::Left
+		} else {
+			Choice::Right
+		})
+	}
+
+	#[test]
+	fn replaying_a_log_rebuilds_the_final_order() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![4, 9, 1, 7, 3, 8]).unwrap();
+		while sorter.make_choice_with(answer) {}
+		let SortState::Done(order) = &sorter.state else {
+			panic!("the sort did not finish");
+		};
+
+		let mut replay = Replay::new(sorter.log().unwrap().clone());
+		while !replay.is_finished() {
+			replay.forward();
+		}
+		assert_eq!(replay.state().placed(), &order[..]);
+	}
+
+	#[test]
+	fn stepping_back_undoes_one_choice() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![2, 5, 3]).unwrap();
+		while sorter.make_choice_with(answer) {}
+
+		let mut replay = Replay::new(sorter.log().unwrap().clone());
+		replay.forward();
+		let pair = replay.state().current_pair().map(|(l, r)| (*l, *r));
+		replay.forward();
+		replay.back();
+		assert_eq!(replay.step(), 1);
+		assert_eq!(replay.state().current_pair().map(|(l, r)| (*l, *r)), pair);
+	}
+}
@@ -0,0 +1,145 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::Item;
+
+const SECONDS_PER_DAY: f32 = 86_400.0;
+
+/// How the items are ordered before a sort starts asking about them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SeedStrategy {
+	/// List order, with older items moved down by the decay setting.
+	#[default]
+	Decayed,
+	/// Items with different tags interleaved, so one tag's items aren't all compared in a row.
+	Balanced,
+}
+
+/// Reorders `items` before a sort so that, with a positive `decay_per_day`, older items start
+/// further down. Each item starts with a score from its list position and loses `decay_per_day`
+/// for every day since it was created.
+pub fn decayed(items: &mut [Item], decay_per_day: f32, now: SystemTime) {
+	if decay_per_day <= 0.0 {
+		return;
+	}
+
+	let mut scored: Vec<(f32, Item)> = items
+		.iter()
+		.enumerate()
+		.map(|(i, item)| {
+			let age_days = now
+				.duration_since(item.created)
+				.map_or(0.0, |age| age.as_secs_f32() / SECONDS_PER_DAY);
+			(-(i as f32) - decay_per_day * age_days, item.clone())
+		})
+		.collect();
+	scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+	for (slot, (_, item)) in items.iter_mut().zip(scored) {
+		*slot = item;
+	}
+}
+
+/// Spreads each tag's items as evenly as possible over the list.
+///
+/// Items keep their order within a tag. They are grouped by their first tag, and untagged
+/// items form a group of their own.
+pub fn balanced(items: &mut Vec<Item>) {
+	let mut groups: Vec<(Option<&str>, Vec<usize>)> = Vec::new();
+	for (i, item) in items.iter().enumerate() {
+		let tag = item.tags.first().map(String::as_str);
+		match groups.iter_mut().find(|(group, _)| *group == tag) {
+			Some((_, members)) => members.push(i),
+			None => groups.push((tag, vec![i])),
+		}
+	}
+
+	// Each item's spot within its group, as a fraction of the way through it.
+	let mut placed: Vec<(f32, usize)> = groups
+		.iter()
+		.flat_map(|(_, members)| {
+			let len = members.len() as f32;
+			members
+				.iter()
+				.enumerate()
+				.map(move |(j, &i)| ((j as f32 + 0.5) / len, i))
+		})
+		.collect();
+	placed.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+	let mut taken: Vec<Option<Item>> = items.drain(..).map(Some).collect();
+	items.extend(placed.into_iter().filter_map(|(_, i)| taken[i].take()));
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+
+	const DAY: Duration = Duration::from_secs(86_400);
+
+	fn descriptions(items: &[Item]) -> Vec<&str> {
+		items.iter().map(|item| item.description.as_str()).collect()
+	}
+
+	fn aged(description: &str, days: u32, now: SystemTime) -> Item {
+		let mut item = Item::new(description.into());
+		item.created = now - DAY * days;
+		item
+	}
+
+	#[test]
+	fn older_items_seed_lower_with_a_positive_decay() {
+		let now = SystemTime::now();
+		let mut items = vec![aged("old", 10, now), aged("new", 0, now)];
+		decayed(&mut items, 1.0, now);
+		assert_eq!(descriptions(&items), ["new", "old"]);
+	}
+
+	#[test]
+	fn a_small_decay_keeps_the_list_order() {
+		let now = SystemTime::now();
+		let mut items = vec![aged("old", 10, now), aged("new", 0, now)];
+		decayed(&mut items, 0.05, now);
+		assert_eq!(descriptions(&items), ["old", "new"]);
+	}
+
+	#[test]
+	fn no_decay_keeps_the_list_order() {
+		let now = SystemTime::now();
+		let mut items = vec![aged("old", 100, now), aged("new", 0, now)];
+		decayed(&mut items, 0.0, now);
+		assert_eq!(descriptions(&items), ["old", "new"]);
+	}
+
+	fn tagged(tags: &[&str]) -> Vec<Item> {
+		tags.iter()
+			.enumerate()
+			.map(|(i, tag)| {
+				let mut item = Item::new(format!("{}{}", tag, i));
+				item.add_tag(tag);
+				item
+			})
+			.collect()
+	}
+
+	#[test]
+	fn balancing_alternates_tags_of_the_same_size() {
+		let mut items = tagged(&["a", "a", "b", "b"]);
+		balanced(&mut items);
+		assert_eq!(descriptions(&items), ["a0", "b2", "a1", "b3"]);
+	}
+
+	#[test]
+	fn balancing_spreads_a_smaller_tag_out() {
+		let mut items = tagged(&["a", "a", "a", "a", "b", "b"]);
+		items.push(Item::new("untagged".into()));
+		balanced(&mut items);
+		assert_eq!(
+			descriptions(&items),
+			["a0", "b4", "a1", "untagged", "a2", "b5", "a3"]
+		);
+	}
+}
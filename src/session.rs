@@ -0,0 +1,127 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{item::Item, sorter::DecisionLog};
+
+const VERSION: u32 = 1;
+
+/// A sort in progress bundled into a single file, so it can be picked up on another machine
+/// exactly where it was left.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Session {
+	version: u32,
+	pub items: Vec<Item>,
+	pub log: Option<DecisionLog<Item>>,
+	pub sort_path: Vec<usize>,
+	pub sort_queue: Vec<Vec<usize>>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+	Malformed(serde_json::Error),
+	UnsupportedVersion(u32),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Malformed(error) => write!(f, "not a session file ({})", error),
+			Self::UnsupportedVersion(version) => {
+				write!(f, "session version {} is not supported", version)
+			},
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct Header {
+	version: u32,
+}
+
+impl Session {
+	pub const fn new(
+		items: Vec<Item>,
+		log: Option<DecisionLog<Item>>,
+		sort_path: Vec<usize>,
+		sort_queue: Vec<Vec<usize>>,
+	) -> Self {
+		Self {
+			version: VERSION,
+			items,
+			log,
+			sort_path,
+			sort_queue,
+		}
+	}
+
+	pub fn to_json(&self) -> serde_json::Result<String> {
+		serde_json::to_string_pretty(self)
+	}
+
+	/// Reads a session back, checking the version header before anything else.
+	pub fn from_json(json: &str) -> Result<Self, Error> {
+		let header: Header = serde_json::from_str(json).map_err(Error::Malformed)?;
+		if header.version != VERSION {
+			return Err(Error::UnsupportedVersion(header.version));
+		}
+		serde_json::from_str(json).map_err(Error::Malformed)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sorter::{Choice, Sorter};
+
+	/// Puts items whose descriptions come first alphabetically first.
+	fn alphabetical(left: &Item, right: &Item) -> Option<Choice> {
+		Some(if left.description < right.description {
+			Choice::Left
+		} else {
+			Choice::Right
+		})
+	}
+
+	#[test]
+	fn a_session_carries_on_where_it_was_left() {
+		let items: Vec<Item> = ["d", "b", "e", "a", "c"]
+			.iter()
+			.map(|description| Item::new((*description).into()))
+			.collect();
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(items.clone()).unwrap();
+		for _ in 0..3 {
+			sorter.make_choice_with(alphabetical);
+		}
+
+		let json = Session::new(items, sorter.log().cloned(), vec![], vec![vec![0]])
+			.to_json()
+			.unwrap();
+		let session = Session::from_json(&json).unwrap();
+		assert_eq!(session.sort_queue, vec![vec![0]]);
+		let resumed = Sorter::from_log(session.log.unwrap());
+
+		let ids = |sorter: &Sorter<Item>| {
+			sorter
+				.state
+				.current_pair()
+				.map(|(left, right)| (left.id, right.id))
+		};
+		assert_eq!(ids(&resumed), ids(&sorter));
+		assert_eq!(resumed.progress(), sorter.progress());
+	}
+
+	#[test]
+	fn another_version_is_refused() {
+		let json = r#"{"version": 99}"#;
+		assert!(matches!(
+			Session::from_json(json),
+			Err(Error::UnsupportedVersion(99))
+		));
+		assert!(matches!(
+			Session::from_json("not json"),
+			Err(Error::Malformed(_))
+		));
+	}
+}
@@ -0,0 +1,369 @@
+use iced::Theme;
+use serde::{Deserialize, Serialize};
+
+use crate::{export::ExportFormat, item::Item, seed::SeedStrategy, sorter::SortStrategy};
+
+/// How tightly the list is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Density {
+	#[default]
+	Comfortable,
+	Compact,
+}
+
+impl Density {
+	/// Space between the widgets of a row, and around the list's sections.
+	pub const fn spacing(self) -> u16 {
+		match self {
+			Self::Comfortable => 20,
+			Self::Compact => 8,
+		}
+	}
+
+	/// Space between the items of the list.
+	pub const fn item_spacing(self) -> u16 {
+		match self {
+			Self::Comfortable => 10,
+			Self::Compact => 2,
+		}
+	}
+
+	pub const fn padding(self) -> u16 {
+		match self {
+			Self::Comfortable => 10,
+			Self::Compact => 4,
+		}
+	}
+
+	pub const fn toggled(self) -> Self {
+		match self {
+			Self::Comfortable => Self::Compact,
+			Self::Compact => Self::Comfortable,
+		}
+	}
+}
+
+/// Whether the window is light or dark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeMode {
+	#[default]
+	Light,
+	Dark,
+}
+
+impl ThemeMode {
+	pub const fn theme(self) -> Theme {
+		match self {
+			Self::Light => Theme::Light,
+			Self::Dark => Theme::Dark,
+		}
+	}
+
+	pub const fn toggled(self) -> Self {
+		match self {
+			Self::Light => Self::Dark,
+			Self::Dark => Self::Light,
+		}
+	}
+}
+
+/// The window's size and where it was last put, kept between launches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+	pub size: (u32, u32),
+	/// Left to the window manager until the window is first moved.
+	pub position: Option<(i32, i32)>,
+}
+
+impl WindowGeometry {
+	/// The monitors can't be asked about before the window opens, so anything further out than
+	/// this is taken to be off every screen.
+	const MAX_EXTENT: u32 = 16_384;
+	/// Too small to show a row of the list.
+	const MIN_SIZE: u32 = 200;
+
+	/// The geometry with a size or position that couldn't be right put back to the default.
+	pub fn validated(self) -> Self {
+		let (width, height) = self.size;
+		let fits = |length: u32| (Self::MIN_SIZE..=Self::MAX_EXTENT).contains(&length);
+		Self {
+			size: if fits(width) && fits(height) {
+				self.size
+			} else {
+				Self::default().size
+			},
+			position: self.position.filter(|&(x, y)| {
+				x.unsigned_abs() <= Self::MAX_EXTENT && y.unsigned_abs() <= Self::MAX_EXTENT
+			}),
+		}
+	}
+}
+
+impl Default for WindowGeometry {
+	fn default() -> Self {
+		Self {
+			size: (500, 800),
+			position: None,
+		}
+	}
+}
+
+/// What every new item starts out with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemTemplate {
+	/// Put in front of each description, unless it was typed already.
+	pub prefix: String,
+	/// Tags added to each item, separated by spaces.
+	pub tags: String,
+	/// Notes each item starts with.
+	pub notes: String,
+}
+
+impl ItemTemplate {
+	pub fn create(&self, input: &str) -> Item {
+		let input = input.trim();
+		let prefix = self.prefix.trim();
+		let mut item = if prefix.is_empty() || input.starts_with(prefix) {
+			Item::new(input.to_string())
+		} else {
+			Item::new(format!("{}{}", self.prefix.trim_start(), input))
+		};
+		for tag in self.tags.split_whitespace() {
+			item.add_tag(tag.trim_start_matches('#'));
+		}
+		item.notes = self.notes.trim().to_string();
+		item
+	}
+}
+
+/// Kept between launches. Any setting missing from the saved file, like one added since, takes
+/// its default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+	/// Lists longer than twice this only show this many items at the top and at the bottom.
+	pub collapse_threshold: usize,
+	/// How much an item's starting position drops per day of age when seeding a sort.
+	pub decay_per_day: f32,
+	/// Whether the item being placed is shown on the left, with the item it is compared against
+	/// on the right.
+	pub left_is_incoming: bool,
+	/// How many choices can be undone. Older ones are forgotten to bound memory on huge lists.
+	pub history_cap: usize,
+	/// Enforce a break after this many choices in a row, if set.
+	pub break_every: Option<usize>,
+	pub break_seconds: u32,
+	/// Ask for a one-line reason after each choice, kept in the decision log.
+	pub ask_rationale: bool,
+	/// Fractions of a sorted list, from the top, where the P0, P1 and P2 badges end.
+	pub badge_cutoffs: [f32; 3],
+	pub density: Density,
+	pub theme: ThemeMode,
+	/// Show how many characters each item's description has.
+	pub show_counts: bool,
+	/// Keep a sort from being left before this many choices, so the order means something.
+	pub min_comparisons: Option<usize>,
+	/// Lock the descriptions of items once a sort has ranked them.
+	pub lock_ranked: bool,
+	/// Answer "equal" on its own when both items have the same description.
+	pub auto_equal_duplicates: bool,
+	/// Ask before closing the window in the middle of a sort.
+	pub warn_on_quit: bool,
+	pub seed_strategy: SeedStrategy,
+	/// Show each new pair right away instead of sliding it in.
+	pub reduce_motion: bool,
+	/// Mirror the comparison and list rows, for right-to-left scripts.
+	pub right_to_left: bool,
+	/// Take every other item to place from the other end of the list, for more varied questions.
+	pub alternate_ends: bool,
+	/// Shuffle the items before a sort, so a list against the order chosen doesn't take the
+	/// most questions.
+	pub shuffle_start: bool,
+	pub sort_strategy: SortStrategy,
+	/// The format last exported to, used by the next export.
+	pub export_format: ExportFormat,
+	/// Show each item's rank at the start of its row.
+	pub show_indices: bool,
+	pub new_item_template: ItemTemplate,
+}
+
+impl Settings {
+	/// Whether a sort that has taken `comparisons` choices so far may be left early.
+	pub fn allows_finish(&self, comparisons: usize) -> bool {
+		self.min_comparisons
+			.is_none_or(|minimum| comparisons >= minimum)
+	}
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			collapse_threshold: 10,
+			decay_per_day: 0.0,
+			left_is_incoming: true,
+			history_cap: 1000,
+			break_every: None,
+			break_seconds: 30,
+			ask_rationale: false,
+			badge_cutoffs: [0.1, 0.3, 0.6],
+			density: Density::default(),
+			theme: ThemeMode::default(),
+			show_counts: false,
+			min_comparisons: None,
+			lock_ranked: false,
+			auto_equal_duplicates: false,
+			warn_on_quit: true,
+			seed_strategy: SeedStrategy::default(),
+			reduce_motion: false,
+			right_to_left: false,
+			alternate_ends: false,
+			shuffle_start: false,
+			sort_strategy: SortStrategy::default(),
+			export_format: ExportFormat::default(),
+			show_indices: true,
+			new_item_template: ItemTemplate::default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn a_usable_geometry_is_kept() {
+		let geometry = WindowGeometry {
+			size: (1024, 768),
+			position: Some((-100, 40)),
+		};
+		assert_eq!(geometry.validated(), geometry);
+	}
+
+	#[test]
+	fn an_unusable_size_or_position_goes_back_to_the_default() {
+		let geometry = WindowGeometry {
+			size: (50, 768),
+			position: Some((100_000, 0)),
+		};
+		assert_eq!(geometry.validated(), WindowGeometry::default());
+	}
+
+	#[test]
+	fn settings_survive_a_round_trip() {
+		let settings = Settings {
+			theme: ThemeMode::Dark,
+			export_format: ExportFormat::Csv,
+			density: Density::Compact,
+			break_every: Some(20),
+			..Settings::default()
+		};
+		let json = serde_json::to_string(&settings).unwrap();
+		let loaded: Settings = serde_json::from_str(&json).unwrap();
+		assert_eq!(loaded.theme, ThemeMode::Dark);
+		assert_eq!(loaded.export_format, ExportFormat::Csv);
+		assert_eq!(loaded.density, Density::Compact);
+		assert_eq!(loaded.break_every, Some(20));
+	}
+
+	#[test]
+	fn missing_settings_take_their_defaults() {
+		let loaded: Settings = serde_json::from_str(r#"{ "theme": "Dark" }"#).unwrap();
+		assert_eq!(loaded.theme, ThemeMode::Dark);
+		assert_eq!(loaded.export_format, ExportFormat::default());
+		assert_eq!(loaded.history_cap, Settings::default().history_cap);
+	}
+
+	#[test]
+	fn new_items_take_the_template() {
+		let template = ItemTemplate {
+			prefix: "Work: ".into(),
+			tags: "#job urgent".into(),
+			notes: "Check with the team".into(),
+		};
+		let item = template.create("  Write report ");
+		assert_eq!(item.description, "Work: Write report");
+		assert_eq!(item.tags, ["job", "urgent"]);
+		assert_eq!(item.notes, "Check with the team");
+	}
+
+	#[test]
+	fn a_typed_prefix_isnt_doubled() {
+		let template = ItemTemplate {
+			prefix: "Work: ".into(),
+			..ItemTemplate::default()
+		};
+		assert_eq!(
+			template.create("Work: Write report").description,
+			"Work: Write report"
+		);
+		assert_eq!(
+			ItemTemplate::default().create("Write report").description,
+			"Write report"
+		);
+	}
+
+	#[test]
+	fn finishing_waits_for_the_minimum_comparisons() {
+		let mut settings = Settings {
+			min_comparisons: Some(3),
+			..Settings::default()
+		};
+		assert!(!settings.allows_finish(2));
+		assert!(settings.allows_finish(3));
+		assert!(settings.allows_finish(4));
+
+		settings.min_comparisons = None;
+		assert!(settings.allows_finish(0));
+	}
+
+	#[test]
+	fn each_density_has_its_spacing() {
+		let comfortable = Density::Comfortable;
+		let compact = comfortable.toggled();
+		assert_eq!(compact, Density::Compact);
+		assert_eq!(compact.toggled(), comfortable);
+		assert_eq!(
+			(
+				comfortable.spacing(),
+				comfortable.item_spacing(),
+				comfortable.padding()
+			),
+			(20, 10, 10)
+		);
+		assert_eq!(
+			(compact.spacing(), compact.item_spacing(), compact.padding()),
+			(8, 2, 4)
+		);
+	}
+
+	#[test]
+	fn the_defaults_are_the_documented_ones() {
+		let settings = Settings::default();
+		assert_eq!(settings.collapse_threshold, 10);
+		assert_eq!(settings.decay_per_day, 0.0);
+		assert!(settings.left_is_incoming);
+		assert_eq!(settings.history_cap, 1000);
+		assert_eq!(settings.break_every, None);
+		assert_eq!(settings.badge_cutoffs, [0.1, 0.3, 0.6]);
+		assert_eq!(settings.density, Density::Comfortable);
+		assert_eq!(settings.min_comparisons, None);
+		assert!(!settings.lock_ranked);
+		assert!(!settings.auto_equal_duplicates);
+		assert!(settings.warn_on_quit);
+		assert!(!settings.reduce_motion);
+		assert!(!settings.shuffle_start);
+		assert_eq!(settings.sort_strategy, SortStrategy::BinaryInsertion);
+		assert_eq!(settings.export_format, ExportFormat::Markdown);
+		assert!(settings.show_indices);
+	}
+
+	#[test]
+	fn a_template_tag_is_added_once() {
+		let template = ItemTemplate {
+			tags: "#home home  garden".into(),
+			..ItemTemplate::default()
+		};
+		assert_eq!(template.create("Mow").tags, ["home", "garden"]);
+	}
+}
@@ -0,0 +1,1861 @@
+use std::{
+	collections::{HashMap, VecDeque},
+	error::Error,
+	fmt, mem,
+};
+#[cfg(feature = "resume")]
+use std::{fs, io, path::Path};
+
+#[cfg(feature = "resume")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::merge_insertion::{self, Replayed};
+
+/// Tells an item apart from the others in a session, so a record of the questions asked can name
+/// it without keeping a copy.
+pub trait Identify {
+	fn id(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Choice {
+	Left,
+	Right,
+	Equal,
+}
+
+impl Choice {
+	pub const fn flipped(self) -> Self {
+		match self {
+			Self::Left => Self::Right,
+			Self::Right => Self::Left,
+			Self::Equal => Self::Equal,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortMode {
+	Sort,
+	Refine,
+}
+
+/// How a sort picks its questions. Both end in the same order for consistent choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortStrategy {
+	/// Each item in turn is placed among the ones before it by halving the range it can go in.
+	#[default]
+	BinaryInsertion,
+	/// Ford–Johnson merge-insertion, which asks fewer questions over the whole sort but has
+	/// nothing in order to show until the end.
+	MergeInsertion,
+}
+
+impl SortStrategy {
+	/// The most questions a sort of `len` items can take.
+	pub fn max_comparisons(self, len: usize) -> usize {
+		match self {
+			Self::BinaryInsertion => max_comparisons(len),
+			Self::MergeInsertion => merge_insertion::max_comparisons(len),
+		}
+	}
+}
+
+/// One choice in a session, with the reason given for it, if any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decision {
+	pub choice: Choice,
+	pub rationale: Option<String>,
+}
+
+/// Everything needed to drive a session again from the start: the seed items and every choice
+/// made since.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionLog<T> {
+	pub mode: SortMode,
+	pub items: Vec<T>,
+	/// Whether every other item was taken from the front of the waiting items, which the
+	/// choices only make sense with.
+	#[serde(default)]
+	pub alternate: bool,
+	#[serde(default)]
+	pub strategy: SortStrategy,
+	/// Set when only this many places at the top were ranked.
+	#[serde(default)]
+	pub top_k: Option<usize>,
+	/// How many of the items, from the front, were already in order, with only the rest placed
+	/// among them.
+	#[serde(default)]
+	pub presorted: usize,
+	/// Not trimmed to the history cap: the session can only be rebuilt from its first choice, and
+	/// each entry is just a choice and any reason given for it.
+	pub choices: Vec<Decision>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "resume", derive(Serialize, Deserialize))]
+pub enum SortState<T> {
+	Empty,
+	/// Binary insertion of `unsorted.last()` (left) against `sorted[mid]` (right). An item
+	/// declared equal is placed right after `sorted[mid]` and shares its rank.
+	Compare {
+		sorted: Vec<T>,
+		unsorted: Vec<T>,
+		lo: usize,
+		hi: usize,
+	},
+	/// A single pass over adjacent pairs, `items[index]` (left) against `items[index + 1]`
+	/// (right).
+	Refine {
+		items: Vec<T>,
+		index: usize,
+	},
+	/// Merge-insertion of `items`, which holds them in their starting order. The question after
+	/// `choices` is `items[left]` against `items[right]`.
+	Merge {
+		items: Vec<T>,
+		choices: Vec<Choice>,
+		left: usize,
+		right: usize,
+	},
+	Done(Vec<T>),
+}
+
+/// What a choice changed, so it can be taken back.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+	Narrow {
+		lo: usize,
+		hi: usize,
+	},
+	Insert {
+		lo: usize,
+		hi: usize,
+		position: usize,
+		/// Whether the next item was then taken from the front.
+		rotated: bool,
+		/// Whether the item placed had been taken from the front.
+		was_rotated: bool,
+	},
+	Refine {
+		index: usize,
+		swapped: bool,
+	},
+	/// Merge-insertion only needs the choice itself taken back.
+	Merge,
+}
+
+impl<T> SortState<T> {
+	pub fn current_pair(&self) -> Option<(&T, &T)> {
+		match self {
+			Self::Compare {
+				sorted,
+				unsorted,
+				lo,
+				hi,
+			} => Some((unsorted.last()?, sorted.get(mid(*lo, *hi))?)),
+			Self::Refine { items, index } => Some((items.get(*index)?, items.get(index + 1)?)),
+			Self::Merge {
+				items, left, right, ..
+			} => Some((items.get(*left)?, items.get(*right)?)),
+			Self::Empty | Self::Done(_) => None,
+		}
+	}
+
+	/// The two items of the current question, to change them without affecting their order.
+	pub fn current_pair_mut(&mut self) -> Option<(&mut T, &mut T)> {
+		match self {
+			Self::Compare {
+				sorted,
+				unsorted,
+				lo,
+				hi,
+			} => Some((unsorted.last_mut()?, sorted.get_mut(mid(*lo, *hi))?)),
+			Self::Refine { items, index } => match items.get_mut(*index..*index + 2)? {
+				[left, right] => Some((left, right)),
+				_ => None,
+			},
+			Self::Merge {
+				items, left, right, ..
+			} => {
+				let (left, right) = (*left, *right);
+				if left.max(right) >= items.len() {
+					return None;
+				}
+				// Split at the later of the two, which is the first item of the second half.
+				let (before, after) = items.split_at_mut(left.max(right));
+				let (earlier, later) = (before.get_mut(left.min(right))?, after.first_mut()?);
+				if left < right {
+					Some((earlier, later))
+				} else {
+					Some((later, earlier))
+				}
+			},
+			Self::Empty | Self::Done(_) => None,
+		}
+	}
+
+	/// Every item of the session, to change them without affecting their order.
+	pub fn items_mut(&mut self) -> impl Iterator<Item = &mut T> {
+		let (first, second): (&mut [T], &mut [T]) = match self {
+			Self::Empty => (&mut [], &mut []),
+			Self::Compare {
+				sorted, unsorted, ..
+			} => (sorted, unsorted),
+			Self::Refine { items, .. } | Self::Merge { items, .. } | Self::Done(items) => {
+				(items, &mut [])
+			},
+		};
+		first.iter_mut().chain(second)
+	}
+
+	/// The items whose relative order is already settled. Merge-insertion only settles them all
+	/// at the end.
+	pub fn placed(&self) -> &[T] {
+		match self {
+			Self::Empty | Self::Merge { .. } => &[],
+			Self::Compare { sorted, .. } => sorted,
+			Self::Refine { items, .. } | Self::Done(items) => items,
+		}
+	}
+
+	/// The top `count` items of a sort under way, or fewer if not that many are placed yet.
+	pub fn leaderboard(&self, count: usize) -> &[T] {
+		match self {
+			Self::Compare { sorted, .. } => &sorted[..count.min(sorted.len())],
+			_ => &[],
+		}
+	}
+
+	/// Up to `count` items that will be brought in after the current one, in the order they will
+	/// be. Which items they are compared against depends on the choices made.
+	pub fn upcoming(&self, count: usize) -> impl Iterator<Item = &T> {
+		let waiting = self.waiting();
+		waiting[..waiting.len().saturating_sub(1)]
+			.iter()
+			.rev()
+			.take(count)
+	}
+
+	/// The items not placed yet, the one being placed now last.
+	pub fn waiting(&self) -> &[T] {
+		match self {
+			Self::Compare { unsorted, .. } => unsorted,
+			_ => &[],
+		}
+	}
+}
+
+const fn mid(lo: usize, hi: usize) -> usize {
+	lo + (hi - lo) / 2
+}
+
+/// Puts `items` in an order picked by `seed`, the same one every time for the same seed.
+fn shuffle<T>(items: &mut [T], mut seed: u64) {
+	// SplitMix64, which is plenty for picking an order.
+	let mut next = || {
+		seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = seed;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	};
+	for i in (1..items.len()).rev() {
+		let j = (next() % (i as u64 + 1)) as usize;
+		items.swap(i, j);
+	}
+}
+
+/// The most questions binary insertion can take to place an item among `n` others, which is
+/// `ceil(log2(n + 1))`.
+const fn worst_case(n: usize) -> usize {
+	(usize::BITS - n.leading_zeros()) as usize
+}
+
+/// The most questions binary insertion can take to sort `len` items, placing each among the ones
+/// before it.
+pub fn max_comparisons(len: usize) -> usize {
+	(1..len).map(worst_case).sum()
+}
+
+/// How much of the order `finish_sorting` wrote was ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinishKind {
+	/// Every item has its place.
+	Complete,
+	/// Only the first `placed` items are in order, and the `remaining` ones after them aren't.
+	Partial { placed: usize, remaining: usize },
+	/// There was no session to finish, so the items were left as they were.
+	Empty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SorterError {
+	/// A sort is still asking questions, starting another would throw its progress away.
+	AlreadySorting,
+	/// There were no items to sort.
+	NothingToCompare,
+	/// No question is waiting for an answer.
+	NotSorting,
+	/// No choice is left to take back.
+	NothingToUndo,
+}
+
+impl fmt::Display for SorterError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::AlreadySorting => write!(f, "a sort is already in progress"),
+			Self::NothingToCompare => write!(f, "there is nothing to sort"),
+			Self::NotSorting => write!(f, "no comparison is waiting for a choice"),
+			Self::NothingToUndo => write!(f, "there is no choice to undo"),
+		}
+	}
+}
+
+impl Error for SorterError {}
+
+/// How far a session has got, in items rather than a single fraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortProgress {
+	/// Items that have a place in the order so far.
+	pub placed: usize,
+	/// Items still waiting to be placed.
+	pub waiting: usize,
+	/// Items at the top that no further choice can move.
+	pub settled: usize,
+}
+
+#[derive(Debug)]
+pub struct SortResult<T> {
+	pub items: Vec<T>,
+	pub comparisons: usize,
+	/// Items the session ended before placing, in their original order, after any a top-K sort
+	/// set aside.
+	pub unranked: Vec<T>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "resume", derive(Serialize, Deserialize))]
+pub struct Sorter<T> {
+	pub state: SortState<T>,
+	comparisons: usize,
+	log: Option<DecisionLog<T>>,
+	/// Every question answered this session, as the ids of the left and the right item and the
+	/// choice.
+	#[cfg_attr(feature = "resume", serde(default))]
+	answered: Vec<(u64, u64, Choice)>,
+	/// Parallel to the placed items, `tied[i]` is set when item `i` shares its rank with item
+	/// `i - 1`.
+	tied: Vec<bool>,
+	#[cfg_attr(feature = "resume", serde(skip))]
+	history: VecDeque<Step>,
+	history_cap: usize,
+	alternate: bool,
+	/// Set while the item being placed was taken from the front of the waiting items, which puts
+	/// it out of their order.
+	#[cfg_attr(feature = "resume", serde(default))]
+	rotated: bool,
+	strategy: SortStrategy,
+	/// Set while only the first this many places are being ranked.
+	#[cfg_attr(feature = "resume", serde(default))]
+	top_k: Option<usize>,
+}
+
+impl<T: Clone + Identify> Sorter<T> {
+	pub const fn new() -> Self {
+		Self {
+			state: SortState::Empty,
+			comparisons: 0,
+			log: None,
+			answered: Vec::new(),
+			tied: Vec::new(),
+			history: VecDeque::new(),
+			history_cap: usize::MAX,
+			alternate: false,
+			rotated: false,
+			strategy: SortStrategy::BinaryInsertion,
+			top_k: None,
+		}
+	}
+
+	/// Rebuilds the session recorded in `log` as it stood after its last choice.
+	pub fn from_log(log: DecisionLog<T>) -> Self {
+		let mut sorter = Self::at_start(&log);
+		for decision in log.choices {
+			if sorter.make_choice(decision.choice).is_err() {
+				break;
+			}
+			if let Some(rationale) = decision.rationale {
+				sorter.explain_last_choice(rationale);
+			}
+		}
+		sorter
+	}
+
+	/// The session recorded in `log` as it stood before its first choice.
+	pub fn at_start(log: &DecisionLog<T>) -> Self {
+		let mut sorter = Self::new();
+		sorter.set_alternate(log.alternate);
+		sorter.set_strategy(log.strategy);
+		let items = log.items.clone();
+		match (log.mode, log.top_k) {
+			(SortMode::Refine, _) => sorter.start_neighbor_refine(items),
+			(SortMode::Sort, Some(k)) => sorter.restart_top_k(items, k),
+			(SortMode::Sort, None) if log.presorted > 0 => {
+				let mut previous = items;
+				let new_items = previous.split_off(log.presorted.min(previous.len()));
+				sorter.restart_incremental(previous, new_items);
+			},
+			(SortMode::Sort, None) => sorter.restart_sorting(items),
+		}
+		sorter
+	}
+
+	pub const fn comparisons(&self) -> usize {
+		self.comparisons
+	}
+
+	pub const fn log(&self) -> Option<&DecisionLog<T>> {
+		self.log.as_ref()
+	}
+
+	/// How many places at the top the session ranks, when it was started with `start_top_k`.
+	/// Only those come first in `Done`; the items after them are in no particular order.
+	pub const fn top_k(&self) -> Option<usize> {
+		self.top_k
+	}
+
+	/// Each question answered this session, oldest first, with the pair as it was asked. Unlike
+	/// the log, this outlives `insert_at`.
+	pub fn history(&self) -> Vec<(&T, &T, Choice)> {
+		let items: HashMap<u64, &T> = self
+			.current_ranking()
+			.into_iter()
+			.map(|item| (item.id(), item))
+			.collect();
+		self.answered
+			.iter()
+			.filter_map(|(left, right, choice)| {
+				Some((*items.get(left)?, *items.get(right)?, *choice))
+			})
+			.collect()
+	}
+
+	/// The choices made so far and the most the whole session can take. Every choice brings the
+	/// estimate down by at least one on top of adding to the count, so the share done only grows.
+	pub fn progress(&self) -> Option<(usize, usize)> {
+		let remaining = match &self.state {
+			SortState::Empty => return None,
+			SortState::Compare {
+				sorted,
+				unsorted,
+				lo,
+				hi,
+			} => {
+				let later: usize = (1..unsorted.len())
+					.map(|i| worst_case(self.search_end(sorted.len() + i)))
+					.sum();
+				worst_case(hi - lo) + later
+			},
+			SortState::Refine { items, index } => items.len() - 1 - index,
+			SortState::Merge { items, choices, .. } => {
+				merge_insertion::max_comparisons(items.len()).saturating_sub(choices.len())
+			},
+			SortState::Done(_) => 0,
+		};
+		Some((self.comparisons, self.comparisons + remaining))
+	}
+
+	/// How far along the session is, from 0 to 1, counting only the choices made. Items placed
+	/// without asking, like the first one or those already in order, don't move it.
+	pub fn completion(&self) -> f32 {
+		match self.progress() {
+			None => 0.0,
+			Some((_, 0)) => 1.0,
+			Some((done, most)) => done as f32 / most as f32,
+		}
+	}
+
+	/// While sorting, any waiting item could still land on top, so nothing is settled before the
+	/// end. A refining pass only moves forward, so everything above the current pair is. A top-K
+	/// sort only counts its top places as placed, not the items set aside below them.
+	pub fn progress_detail(&self) -> SortProgress {
+		match &self.state {
+			SortState::Empty => SortProgress::default(),
+			SortState::Compare {
+				sorted, unsorted, ..
+			} => SortProgress {
+				placed: self.search_end(sorted.len()),
+				waiting: unsorted.len(),
+				settled: 0,
+			},
+			SortState::Refine { items, index } => SortProgress {
+				placed: items.len(),
+				waiting: 0,
+				settled: *index,
+			},
+			SortState::Merge { items, .. } => SortProgress {
+				placed: 0,
+				waiting: items.len(),
+				settled: 0,
+			},
+			SortState::Done(items) => SortProgress {
+				placed: self.search_end(items.len()),
+				waiting: 0,
+				settled: self.search_end(items.len()),
+			},
+		}
+	}
+
+	/// Keeps at most `cap` choices available to undo, forgetting the oldest ones first.
+	pub fn set_history_cap(&mut self, cap: usize) {
+		self.history_cap = cap;
+		self.trim_history();
+	}
+
+	/// Takes every other item to place from the front of the waiting items instead of the back,
+	/// for more varied questions. The order comes out the same either way. Has no effect on a sort
+	/// already under way.
+	pub const fn set_alternate(&mut self, alternate: bool) {
+		if !matches!(self.state, SortState::Compare { .. }) {
+			self.alternate = alternate;
+		}
+	}
+
+	/// Chooses how the next sort picks its questions. Has no effect on a sort already under way.
+	pub const fn set_strategy(&mut self, strategy: SortStrategy) {
+		if !matches!(
+			self.state,
+			SortState::Compare { .. } | SortState::Merge { .. }
+		) {
+			self.strategy = strategy;
+		}
+	}
+
+	/// Starts sorting `items`, unless a sort is already under way or there is nothing to sort.
+	pub fn start_sorting(&mut self, items: Vec<T>) -> Result<(), SorterError> {
+		self.can_start(&items)?;
+		self.restart_sorting(items);
+		Ok(())
+	}
+
+	/// Starts sorting `items` in an order shuffled by `seed`, so an input that is against the
+	/// order chosen doesn't make for the most questions. The log keeps the shuffled order, so a
+	/// replay asks the same questions.
+	pub fn start_sorting_shuffled(
+		&mut self,
+		mut items: Vec<T>,
+		seed: u64,
+	) -> Result<(), SorterError> {
+		self.can_start(&items)?;
+		shuffle(&mut items, seed);
+		self.restart_sorting(items);
+		Ok(())
+	}
+
+	/// Starts sorting `items`, discarding any sort in progress.
+	pub fn restart_sorting(&mut self, items: Vec<T>) {
+		self.top_k = None;
+		self.begin(SortMode::Sort, &items);
+		if self.strategy == SortStrategy::MergeInsertion && !items.is_empty() {
+			self.tied.clear();
+			self.state = self.merge(items, Vec::new());
+			return;
+		}
+		self.insert_all(items);
+	}
+
+	/// Starts finding the `k` highest of `items` in order, unless a sort is already under way or
+	/// there is nothing to sort. Each item only has to be compared against the top `k` placed so
+	/// far, and one that doesn't make it in is set aside below them without further questions.
+	/// Always uses binary insertion.
+	pub fn start_top_k(&mut self, items: Vec<T>, k: usize) -> Result<(), SorterError> {
+		self.can_start(&items)?;
+		self.restart_top_k(items, k);
+		Ok(())
+	}
+
+	/// Starts finding the `k` highest of `items`, discarding any sort in progress.
+	pub fn restart_top_k(&mut self, items: Vec<T>, k: usize) {
+		self.top_k = Some(k.max(1));
+		self.begin(SortMode::Sort, &items);
+		self.insert_all(items);
+	}
+
+	/// Starts placing `new_items` among `previous_sorted`, which is taken to be in order already,
+	/// unless a sort is already under way or there are no new items. Every question has one of
+	/// the new items on the left. Always uses binary insertion.
+	pub fn start_incremental(
+		&mut self,
+		previous_sorted: Vec<T>,
+		new_items: Vec<T>,
+	) -> Result<(), SorterError> {
+		self.can_start(&new_items)?;
+		self.restart_incremental(previous_sorted, new_items);
+		Ok(())
+	}
+
+	/// Starts placing `new_items` among `previous_sorted`, discarding any sort in progress. Items
+	/// the last sort declared equal stay tied if they are still next to each other.
+	pub fn restart_incremental(&mut self, previous_sorted: Vec<T>, mut new_items: Vec<T>) {
+		let tied = self.carried_ties(&previous_sorted);
+		self.top_k = None;
+		let presorted = previous_sorted.len();
+		let mut items = previous_sorted;
+		items.extend(new_items.iter().cloned());
+		self.begin(SortMode::Sort, &items);
+		if let Some(log) = &mut self.log {
+			log.presorted = presorted;
+		}
+		items.truncate(presorted);
+		if items.is_empty() {
+			self.insert_all(new_items);
+			return;
+		}
+		new_items.reverse();
+		self.tied = tied;
+		self.state = self.advance(items, new_items);
+	}
+
+	/// Which of `items` shared a rank with the one before them in the order held now, with any
+	/// items left out in between.
+	fn carried_ties(&self, items: &[T]) -> Vec<bool> {
+		let places: HashMap<u64, usize> = self
+			.state
+			.placed()
+			.iter()
+			.enumerate()
+			.map(|(i, item)| (item.id(), i))
+			.collect();
+		let place = |item: &T| places.get(&item.id()).copied();
+		let mut tied = vec![false; items.len()];
+		for (i, pair) in items.windows(2).enumerate() {
+			if let (Some(above), Some(below)) = (place(&pair[0]), place(&pair[1])) {
+				tied[i + 1] = above < below && self.tied[above + 1..=below].iter().all(|&tie| tie);
+			}
+		}
+		tied
+	}
+
+	/// Starts a single pass over an already ordered list, asking only about adjacent pairs and
+	/// swapping a pair whenever the lower one is chosen.
+	pub fn start_neighbor_refine(&mut self, sorted: Vec<T>) {
+		self.top_k = None;
+		self.begin(SortMode::Refine, &sorted);
+		self.tied = vec![false; sorted.len()];
+		self.state = match sorted.len() {
+			0 => SortState::Empty,
+			1 => SortState::Done(sorted),
+			_ => SortState::Refine {
+				items: sorted,
+				index: 0,
+			},
+		};
+	}
+
+	/// Answers the current question. Fails when no question is waiting.
+	pub fn make_choice(&mut self, choice: Choice) -> Result<(), SorterError> {
+		if matches!(self.state, SortState::Empty | SortState::Done(_)) {
+			return Err(SorterError::NotSorting);
+		}
+		if let Some((left, right)) = self.state.current_pair() {
+			self.answered.push((left.id(), right.id(), choice));
+		}
+		self.state = match mem::replace(&mut self.state, SortState::Empty) {
+			SortState::Compare {
+				mut sorted,
+				mut unsorted,
+				mut lo,
+				mut hi,
+			} => {
+				let (old_lo, old_hi) = (lo, hi);
+				let position = match choice {
+					Choice::Left => {
+						hi = mid(lo, hi);
+						(lo >= hi).then_some(lo)
+					},
+					Choice::Right => {
+						lo = mid(lo, hi) + 1;
+						(lo >= hi).then_some(lo)
+					},
+					Choice::Equal => Some(mid(lo, hi) + 1),
+				};
+				match position {
+					None => {
+						self.record(
+							choice,
+							Step::Narrow {
+								lo: old_lo,
+								hi: old_hi,
+							},
+						);
+						SortState::Compare {
+							sorted,
+							unsorted,
+							lo,
+							hi,
+						}
+					},
+					Some(position) => {
+						// Below the top `k`, the item is set aside at the bottom without a rank.
+						let below = self.top_k.is_some_and(|k| position >= k);
+						let position = if below { sorted.len() } else { position };
+						// Landing between two tied items joins their group.
+						let tied = !below
+							&& (choice == Choice::Equal
+								|| self.tied.get(position).copied().unwrap_or(false));
+						if let Some(item) = unsorted.pop() {
+							sorted.insert(position, item);
+							self.tied.insert(position, tied);
+						}
+						let rotated = self.alternate && sorted.len() % 2 == 0 && unsorted.len() > 1;
+						if rotated {
+							unsorted.rotate_left(1);
+						}
+						self.record(
+							choice,
+							Step::Insert {
+								lo: old_lo,
+								hi: old_hi,
+								position,
+								rotated,
+								was_rotated: self.rotated,
+							},
+						);
+						self.rotated = rotated;
+						self.advance(sorted, unsorted)
+					},
+				}
+			},
+			SortState::Refine { mut items, index } => {
+				let swapped = choice == Choice::Right;
+				self.record(choice, Step::Refine { index, swapped });
+				if swapped {
+					items.swap(index, index + 1);
+				}
+				if index + 2 < items.len() {
+					SortState::Refine {
+						items,
+						index: index + 1,
+					}
+				} else {
+					SortState::Done(items)
+				}
+			},
+			SortState::Merge {
+				items, mut choices, ..
+			} => {
+				self.record(choice, Step::Merge);
+				choices.push(choice);
+				self.merge(items, choices)
+			},
+			state @ (SortState::Empty | SortState::Done(_)) => state,
+		};
+		Ok(())
+	}
+
+	/// Answers the current question with what `f` says about its left and right item, if it has
+	/// an answer. Otherwise the question is left waiting for a choice made by hand. Returns
+	/// whether a choice was made.
+	pub fn make_choice_with<F: FnMut(&T, &T) -> Option<Choice>>(&mut self, mut f: F) -> bool {
+		match self
+			.state
+			.current_pair()
+			.and_then(|(left, right)| f(left, right))
+		{
+			Some(choice) => self.make_choice(choice).is_ok(),
+			None => false,
+		}
+	}
+
+	/// Attaches a reason to the most recent choice.
+	pub fn explain_last_choice(&mut self, rationale: String) {
+		if let Some(decision) = self.log.as_mut().and_then(|log| log.choices.last_mut()) {
+			decision.rationale = Some(rationale);
+		}
+	}
+
+	/// Applies `edit` to every copy of the items the session holds, the ones in play as well as
+	/// the ones the log starts from, so an edit shows the same wherever they turn up again. It must
+	/// not change how the items compare.
+	pub fn edit_items<F: FnMut(&mut T)>(&mut self, mut edit: F) {
+		match &mut self.state {
+			SortState::Empty => {},
+			SortState::Compare {
+				sorted, unsorted, ..
+			} => sorted.iter_mut().chain(unsorted).for_each(&mut edit),
+			SortState::Refine { items, .. }
+			| SortState::Merge { items, .. }
+			| SortState::Done(items) => items.iter_mut().for_each(&mut edit),
+		}
+		if let Some(log) = &mut self.log {
+			log.items.iter_mut().for_each(&mut edit);
+		}
+	}
+
+	/// Places `item` directly at `position` among the items sorted so far, without asking
+	/// anything, and keeps the current question's range pointing at the same items. Only works
+	/// while comparing. Earlier choices can no longer be undone or replayed afterwards, so the
+	/// history and the log are dropped.
+	pub fn insert_at(&mut self, position: usize, item: T) -> bool {
+		let SortState::Compare { sorted, lo, hi, .. } = &mut self.state else {
+			return false;
+		};
+		let position = position.min(sorted.len());
+		if position < *lo {
+			*lo += 1;
+			*hi += 1;
+		} else if position < *hi {
+			*hi += 1;
+		}
+		let tied = self.tied.get(position).copied().unwrap_or(false);
+		sorted.insert(position, item);
+		self.tied.insert(position, tied);
+		self.history.clear();
+		self.log = None;
+		true
+	}
+
+	/// The order as far as it is known: the placed items, then the ones still waiting in the
+	/// order they were given. A merge-insertion sort has nothing placed before it is done.
+	pub fn current_ranking(&self) -> Vec<&T> {
+		if let SortState::Merge { items, .. } = &self.state {
+			return items.iter().collect();
+		}
+		let waiting = self.state.waiting();
+		// An item taken from the front is the last of the rest in their starting order.
+		let (rest, last) = match waiting.split_last() {
+			Some((last, rest)) if self.rotated => (rest, Some(last)),
+			_ => (waiting, None),
+		};
+		self.state
+			.placed()
+			.iter()
+			.chain(rest.iter().rev())
+			.chain(last)
+			.collect()
+	}
+
+	/// Whether the last session ran to the end and its result is still held.
+	pub const fn is_done(&self) -> bool {
+		matches!(self.state, SortState::Done(_))
+	}
+
+	pub fn can_undo(&self) -> bool {
+		!self.history.is_empty()
+	}
+
+	/// Takes back the most recent choice. Fails when there is nothing left to undo, including
+	/// choices that fell out of the history cap.
+	pub fn undo_choice(&mut self) -> Result<(), SorterError> {
+		let step = self.history.pop_back().ok_or(SorterError::NothingToUndo)?;
+		self.comparisons -= 1;
+		self.answered.pop();
+		if let Some(log) = &mut self.log {
+			log.choices.pop();
+		}
+
+		self.state = match (mem::replace(&mut self.state, SortState::Empty), step) {
+			(
+				SortState::Compare {
+					sorted, unsorted, ..
+				},
+				Step::Narrow { lo, hi },
+			) => SortState::Compare {
+				sorted,
+				unsorted,
+				lo,
+				hi,
+			},
+			(
+				SortState::Compare {
+					sorted,
+					mut unsorted,
+					..
+				},
+				Step::Insert {
+					lo,
+					hi,
+					position,
+					rotated,
+					was_rotated,
+				},
+			) => {
+				if rotated {
+					unsorted.rotate_right(1);
+				}
+				self.rotated = was_rotated;
+				self.take_back(sorted, unsorted, lo, hi, position)
+			},
+			(
+				SortState::Done(sorted),
+				Step::Insert {
+					lo,
+					hi,
+					position,
+					was_rotated,
+					..
+				},
+			) => {
+				self.rotated = was_rotated;
+				self.take_back(sorted, Vec::new(), lo, hi, position)
+			},
+			(
+				SortState::Refine { mut items, .. } | SortState::Done(mut items),
+				Step::Refine { index, swapped },
+			) => {
+				if swapped {
+					items.swap(index, index + 1);
+				}
+				SortState::Refine { items, index }
+			},
+			(
+				SortState::Merge {
+					items, mut choices, ..
+				},
+				Step::Merge,
+			) => {
+				choices.pop();
+				self.merge(items, choices)
+			},
+			// The starting order is gone from a finished merge, so it comes from the log.
+			(state @ SortState::Done(_), Step::Merge) => match self.log.as_ref().map(|log| {
+				let choices = log.choices.iter().map(|decision| decision.choice);
+				(log.items.clone(), choices.collect())
+			}) {
+				Some((items, choices)) => self.merge(items, choices),
+				None => state,
+			},
+			(state, _) => state,
+		};
+		Ok(())
+	}
+
+	/// The placed items, highest first, with their rank and a score. Items declared equal share
+	/// both. Ranks count up from 1 without gaps, and scores go down from 1 at the top in even steps
+	/// per rank. A top-K sort only ranks its first `k` items.
+	pub fn ranked_iter(&self) -> impl Iterator<Item = (usize, &T, f32)> {
+		let ranked = self.top_k.unwrap_or(usize::MAX);
+		let ranks = self
+			.tied
+			.iter()
+			.take(ranked)
+			.filter(|&&tied| !tied)
+			.count()
+			.max(1) as f32;
+		self.state
+			.placed()
+			.iter()
+			.zip(&self.tied)
+			.take(ranked)
+			.scan(0, move |rank, (item, &tied)| {
+				if !tied {
+					*rank += 1;
+				}
+				let score = 1.0 - (*rank - 1) as f32 / ranks;
+				Some((*rank, item, score))
+			})
+	}
+
+	/// The placed items grouped by shared rank, highest first.
+	pub fn clustered_result(&self) -> Vec<Vec<T>> {
+		let mut clusters: Vec<Vec<T>> = Vec::new();
+		for (rank, item, _) in self.ranked_iter() {
+			match clusters.get_mut(rank - 1) {
+				Some(cluster) => cluster.push(item.clone()),
+				None => clusters.push(vec![item.clone()]),
+			}
+		}
+		clusters
+	}
+
+	/// Ends the session and hands back what it produced. A finished sort stays available as
+	/// `Done`. Mid-sort, the placed items are returned in order and the ones still waiting are
+	/// returned separately as unranked, and the progress is discarded. The unranked items keep
+	/// the order they were given in, whichever end the questions took them from. The items a
+	/// top-K sort set aside below its first `k` count as unranked too.
+	pub fn take_result(&mut self) -> SortResult<T> {
+		let comparisons = self.comparisons;
+		let (mut items, mut unranked) = match mem::replace(&mut self.state, SortState::Empty) {
+			SortState::Empty => (Vec::new(), Vec::new()),
+			SortState::Compare {
+				sorted,
+				mut unsorted,
+				..
+			} => {
+				unsorted.reverse();
+				// An item taken from the front is the last of the rest in their starting order.
+				if self.rotated {
+					unsorted.rotate_left(1);
+				}
+				(sorted, unsorted)
+			},
+			SortState::Refine { items, .. } => (items, Vec::new()),
+			SortState::Merge { items, .. } => (Vec::new(), items),
+			SortState::Done(sorted) => {
+				let items = sorted.clone();
+				self.state = SortState::Done(sorted);
+				(items, Vec::new())
+			},
+		};
+		if let Some(k) = self.top_k.filter(|&k| k < items.len()) {
+			let mut below = items.split_off(k);
+			below.append(&mut unranked);
+			unranked = below;
+		}
+		SortResult {
+			items,
+			comparisons,
+			unranked,
+		}
+	}
+
+	/// Writes the ranking into `items`, with any unranked items after the ranked ones, and says
+	/// how much of it was ranked.
+	pub fn finish_sorting(&mut self, items: &mut Vec<T>) -> FinishKind {
+		if matches!(self.state, SortState::Empty) {
+			return FinishKind::Empty;
+		}
+		let SortResult {
+			items: mut ranked,
+			mut unranked,
+			..
+		} = self.take_result();
+		let kind = if unranked.is_empty() {
+			FinishKind::Complete
+		} else {
+			FinishKind::Partial {
+				placed: ranked.len(),
+				remaining: unranked.len(),
+			}
+		};
+		ranked.append(&mut unranked);
+		*items = ranked;
+		kind
+	}
+
+	fn begin(&mut self, mode: SortMode, items: &[T]) {
+		self.comparisons = 0;
+		self.rotated = false;
+		self.history.clear();
+		self.answered.clear();
+		self.log = Some(DecisionLog {
+			mode,
+			items: items.to_vec(),
+			alternate: self.alternate,
+			strategy: self.strategy,
+			top_k: self.top_k,
+			presorted: 0,
+			choices: Vec::new(),
+		});
+	}
+
+	fn record(&mut self, choice: Choice, step: Step) {
+		self.comparisons += 1;
+		if let Some(log) = &mut self.log {
+			log.choices.push(Decision {
+				choice,
+				rationale: None,
+			});
+		}
+		self.history.push_back(step);
+		self.trim_history();
+	}
+
+	fn take_back(
+		&mut self,
+		mut sorted: Vec<T>,
+		mut unsorted: Vec<T>,
+		lo: usize,
+		hi: usize,
+		position: usize,
+	) -> SortState<T> {
+		unsorted.push(sorted.remove(position));
+		self.tied.remove(position);
+		SortState::Compare {
+			sorted,
+			unsorted,
+			lo,
+			hi,
+		}
+	}
+
+	fn trim_history(&mut self) {
+		while self.history.len() > self.history_cap {
+			self.history.pop_front();
+		}
+	}
+
+	/// Where merge-insertion of `items` stands after `choices`, with the ties filled in once it is
+	/// done.
+	fn merge(&mut self, items: Vec<T>, choices: Vec<Choice>) -> SortState<T> {
+		match merge_insertion::replay(items.len(), &choices) {
+			Replayed::Ask(left, right) => SortState::Merge {
+				items,
+				choices,
+				left,
+				right,
+			},
+			Replayed::Sorted { order, tied } => {
+				self.tied = tied;
+				SortState::Done(order.into_iter().map(|i| items[i].clone()).collect())
+			},
+		}
+	}
+
+	fn advance(&self, sorted: Vec<T>, unsorted: Vec<T>) -> SortState<T> {
+		if unsorted.is_empty() {
+			SortState::Done(sorted)
+		} else {
+			SortState::Compare {
+				lo: 0,
+				hi: self.search_end(sorted.len()),
+				sorted,
+				unsorted,
+			}
+		}
+	}
+
+	/// How many of `placed` items a new one is searched among.
+	fn search_end(&self, placed: usize) -> usize {
+		self.top_k.map_or(placed, |k| placed.min(k))
+	}
+
+	const fn can_start(&self, items: &[T]) -> Result<(), SorterError> {
+		if matches!(
+			self.state,
+			SortState::Compare { .. } | SortState::Merge { .. }
+		) {
+			return Err(SorterError::AlreadySorting);
+		}
+		if items.is_empty() {
+			return Err(SorterError::NothingToCompare);
+		}
+		Ok(())
+	}
+
+	/// Binary insertion of `items`, placing them in order.
+	fn insert_all(&mut self, mut items: Vec<T>) {
+		items.reverse();
+		self.state = match items.pop() {
+			None => SortState::Empty,
+			Some(first) => {
+				self.tied = vec![false];
+				self.advance(vec![first], items)
+			},
+		};
+	}
+}
+
+#[cfg(feature = "resume")]
+impl<T: Clone + Identify> Sorter<T> {
+	/// Writes the session to `path` so it can be picked up again where it was left, though
+	/// without anything to undo.
+	pub fn save_state(&self, path: &Path) -> io::Result<()>
+	where
+		T: Serialize,
+	{
+		fs::write(path, serde_json::to_string(self)?)
+	}
+
+	pub fn load_state(path: &Path) -> io::Result<Self>
+	where
+		T: DeserializeOwned,
+	{
+		Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+	}
+}
+
+impl<T: Clone + Identify> Default for Sorter<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	impl Identify for u32 {
+		fn id(&self) -> u64 {
+			u64::from(*self)
+		}
+	}
+
+	/// Puts larger numbers first.
+	fn descending(left: &u32, right: &u32) -> Option<Choice> {
+		Some(if left > right {
+			Choice::Left
+		} else {
+			Choice::Right
+		})
+	}
+
+	fn sorted(sorter: &Sorter<u32>) -> Vec<u32> {
+		match &sorter.state {
+			SortState::Done(items) => items.clone(),
+			_ => panic!("the sort did not finish"),
+		}
+	}
+
+	#[test]
+	fn undo_stops_at_the_history_cap() {
+		let mut sorter = Sorter::new();
+		sorter.set_history_cap(2);
+		sorter.start_sorting(vec![3, 1, 4, 1, 5, 9, 2, 6]).unwrap();
+		for _ in 0..5 {
+			sorter.make_choice_with(descending);
+			assert!(sorter.history.len() <= 2);
+		}
+		assert!(sorter.undo_choice().is_ok());
+		assert!(sorter.undo_choice().is_ok());
+		assert!(matches!(
+			sorter.undo_choice(),
+			Err(SorterError::NothingToUndo)
+		));
+		assert!(!sorter.can_undo());
+	}
+
+	#[test]
+	fn the_log_keeps_every_choice_past_the_history_cap() {
+		let mut sorter = Sorter::new();
+		sorter.set_history_cap(1);
+		sorter.start_sorting(vec![3, 1, 4, 1, 5, 9, 2, 6]).unwrap();
+		while sorter.make_choice_with(descending) {}
+		let log = sorter.log().unwrap().clone();
+		assert_eq!(log.choices.len(), sorter.comparisons());
+		assert_eq!(sorted(&Sorter::from_log(log)), sorted(&sorter));
+	}
+
+	#[test]
+	fn completion_counts_only_answered_choices() {
+		let mut sorter = Sorter::new();
+		assert_eq!(sorter.completion(), 0.0);
+		sorter.start_sorting(vec![1, 2, 3, 4]).unwrap();
+		assert_eq!(sorter.completion(), 0.0);
+		sorter.make_choice_with(descending);
+		assert!(sorter.completion() > 0.0);
+		while sorter.make_choice_with(descending) {}
+		assert_eq!(sorter.completion(), 1.0);
+	}
+
+	fn range(sorter: &Sorter<u32>) -> (usize, usize) {
+		match &sorter.state {
+			SortState::Compare { lo, hi, .. } => (*lo, *hi),
+			_ => panic!("not comparing"),
+		}
+	}
+
+	#[test]
+	fn inserting_keeps_the_current_question() {
+		let mut sorter = Sorter::new();
+		sorter
+			.start_sorting(vec![10, 20, 30, 40, 50, 60, 70, 80, 35])
+			.unwrap();
+		while sorter.state.placed().len() < 8 {
+			sorter.make_choice_with(descending);
+		}
+		// 35 is first compared with 40 and goes below it, leaving a range that starts past the top.
+		sorter.make_choice_with(descending);
+		let pair = sorter.state.current_pair().map(|(l, r)| (*l, *r));
+		let (lo, hi) = range(&sorter);
+
+		assert!(sorter.insert_at(hi, 99));
+		assert_eq!(range(&sorter), (lo, hi));
+		assert_eq!(sorter.state.current_pair().map(|(l, r)| (*l, *r)), pair);
+
+		assert!(sorter.insert_at(0, 98));
+		assert_eq!(range(&sorter), (lo + 1, hi + 1));
+		assert_eq!(sorter.state.current_pair().map(|(l, r)| (*l, *r)), pair);
+	}
+
+	#[test]
+	fn inserting_past_the_end_appends() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![1, 2, 3]).unwrap();
+		assert!(sorter.insert_at(100, 7));
+		assert_eq!(sorter.state.placed().last(), Some(&7));
+		assert!(sorter.log().is_none());
+		assert!(!sorter.can_undo());
+	}
+
+	#[test]
+	fn inserting_needs_a_sort_under_way() {
+		let mut sorter = Sorter::new();
+		assert!(!sorter.insert_at(0, 1));
+	}
+
+	/// Puts larger numbers first and calls numbers in the same ten equal.
+	fn by_tens(left: &u32, right: &u32) -> Option<Choice> {
+		Some(match (left / 10).cmp(&(right / 10)) {
+			std::cmp::Ordering::Greater => Choice::Left,
+			std::cmp::Ordering::Less => Choice::Right,
+			std::cmp::Ordering::Equal => Choice::Equal,
+		})
+	}
+
+	#[test]
+	fn ranks_count_up_and_scores_go_down() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![5, 31, 12, 34, 18, 40]).unwrap();
+		while sorter.make_choice_with(by_tens) {}
+		let ranked: Vec<_> = sorter.ranked_iter().collect();
+		assert_eq!(ranked.len(), 6);
+		assert_eq!(ranked[0].0, 1);
+		assert_eq!(ranked[0].2, 1.0);
+		for pair in ranked.windows(2) {
+			let ((rank, _, score), (next_rank, _, next_score)) = (pair[0], pair[1]);
+			assert!(next_rank == rank || next_rank == rank + 1);
+			assert!(next_score <= score);
+		}
+		assert_eq!(ranked.last().unwrap().0, 4);
+	}
+
+	#[test]
+	fn items_declared_equal_share_a_cluster() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![5, 31, 12, 34, 18, 40]).unwrap();
+		while sorter.make_choice_with(by_tens) {}
+		let mut clusters = sorter.clustered_result();
+		for cluster in &mut clusters {
+			cluster.sort_unstable();
+		}
+		assert_eq!(
+			clusters,
+			vec![vec![40], vec![31, 34], vec![12, 18], vec![5]]
+		);
+	}
+
+	#[test]
+	fn progress_of_a_full_sort() {
+		let mut sorter = Sorter::new();
+		assert_eq!(sorter.progress_detail(), SortProgress::default());
+		sorter.start_sorting(vec![1, 2, 3, 4]).unwrap();
+		assert_eq!(
+			sorter.progress_detail(),
+			SortProgress {
+				placed: 1,
+				waiting: 3,
+				settled: 0,
+			}
+		);
+		while sorter.make_choice_with(descending) {}
+		assert_eq!(
+			sorter.progress_detail(),
+			SortProgress {
+				placed: 4,
+				waiting: 0,
+				settled: 4,
+			}
+		);
+	}
+
+	#[test]
+	fn progress_of_a_top_k_sort_leaves_out_the_set_aside() {
+		let mut sorter = Sorter::new();
+		sorter.start_top_k(vec![1, 2, 3, 4, 5, 6], 2).unwrap();
+		while sorter.progress_detail().waiting > 1 {
+			sorter.make_choice_with(descending);
+		}
+		assert_eq!(sorter.progress_detail().placed, 2);
+		while sorter.make_choice_with(descending) {}
+		assert_eq!(
+			sorter.progress_detail(),
+			SortProgress {
+				placed: 2,
+				waiting: 0,
+				settled: 2,
+			}
+		);
+	}
+
+	#[test]
+	fn progress_of_a_refining_pass() {
+		let mut sorter = Sorter::new();
+		sorter.start_neighbor_refine(vec![3, 2, 1]);
+		sorter.make_choice_with(descending);
+		assert_eq!(
+			sorter.progress_detail(),
+			SortProgress {
+				placed: 3,
+				waiting: 0,
+				settled: 1,
+			}
+		);
+	}
+
+	#[test]
+	fn progress_of_an_incremental_sort() {
+		let mut sorter = Sorter::new();
+		sorter.start_incremental(vec![9, 5, 1], vec![7, 3]).unwrap();
+		assert_eq!(
+			sorter.progress_detail(),
+			SortProgress {
+				placed: 3,
+				waiting: 2,
+				settled: 0,
+			}
+		);
+	}
+
+	#[test]
+	fn an_edit_reaches_the_log() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![3, 1, 2]).unwrap();
+		sorter.make_choice_with(descending);
+		sorter.edit_items(|item| {
+			if *item == 1 {
+				*item = 10;
+			}
+		});
+		let log = sorter.log().unwrap();
+		assert!(!log.items.contains(&1));
+		assert!(log.items.contains(&10));
+	}
+
+	#[test]
+	fn the_history_names_each_pair_asked() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![1, 3, 2]).unwrap();
+		let mut asked = Vec::new();
+		while let Some((&left, &right)) = sorter.state.current_pair() {
+			asked.push((left, right));
+			sorter.make_choice_with(descending);
+		}
+		let history: Vec<_> = sorter
+			.history()
+			.into_iter()
+			.map(|(&left, &right, _)| (left, right))
+			.collect();
+		assert_eq!(history, asked);
+
+		sorter.undo_choice().unwrap();
+		assert_eq!(sorter.history().len(), asked.len() - 1);
+	}
+
+	#[test]
+	fn placing_new_items_keeps_the_ties() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![5, 31, 34, 40]).unwrap();
+		while sorter.make_choice_with(by_tens) {}
+		let mut previous = Vec::new();
+		sorter.finish_sorting(&mut previous);
+
+		sorter.start_incremental(previous, vec![12, 36]).unwrap();
+		while sorter.make_choice_with(by_tens) {}
+		let mut clusters = sorter.clustered_result();
+		for cluster in &mut clusters {
+			cluster.sort_unstable();
+		}
+		assert_eq!(clusters, [vec![40], vec![31, 34, 36], vec![12], vec![5]]);
+	}
+
+	fn comparisons_to_sort(start: impl FnOnce(&mut Sorter<u32>)) -> usize {
+		let mut sorter = Sorter::new();
+		start(&mut sorter);
+		while sorter.make_choice_with(descending) {}
+		assert!(sorter.is_done());
+		sorter.comparisons()
+	}
+
+	/// The questions taken on average over a few shuffles of `input`.
+	fn shuffled_comparisons(input: &[u32]) -> usize {
+		let total: usize = (0..20)
+			.map(|seed| {
+				comparisons_to_sort(|sorter| {
+					sorter.start_sorting_shuffled(input.to_vec(), seed).unwrap();
+				})
+			})
+			.sum();
+		total / 20
+	}
+
+	#[test]
+	fn shuffling_takes_fewer_questions_for_input_against_the_order() {
+		let against: Vec<u32> = (0..100).collect();
+		let plain = comparisons_to_sort(|sorter| sorter.start_sorting(against.clone()).unwrap());
+		assert!(shuffled_comparisons(&against) < plain);
+	}
+
+	#[test]
+	fn shuffled_the_starting_order_barely_matters() {
+		let against: Vec<u32> = (0..100).collect();
+		let along: Vec<u32> = (0..100).rev().collect();
+		let (against, along) = (shuffled_comparisons(&against), shuffled_comparisons(&along));
+		assert!(against.abs_diff(along) * 20 < along);
+	}
+
+	#[test]
+	fn the_same_seed_shuffles_the_same_way() {
+		let mut first = Sorter::new();
+		first.start_sorting_shuffled((0..20).collect(), 7).unwrap();
+		let mut second = Sorter::new();
+		second.start_sorting_shuffled((0..20).collect(), 7).unwrap();
+		assert_eq!(first.log().unwrap().items, second.log().unwrap().items);
+	}
+
+	#[test]
+	fn one_disagreement_in_a_refining_pass_makes_one_swap() {
+		let mut sorter = Sorter::new();
+		sorter.start_neighbor_refine(vec![6, 5, 3, 4, 2, 1]);
+		let mut asked = 0;
+		while sorter.make_choice_with(descending) {
+			asked += 1;
+		}
+		assert_eq!(asked, 5);
+		assert_eq!(sorted(&sorter), vec![6, 5, 4, 3, 2, 1]);
+		let swaps = sorter
+			.log()
+			.unwrap()
+			.choices
+			.iter()
+			.filter(|decision| decision.choice == Choice::Right)
+			.count();
+		assert_eq!(swaps, 1);
+	}
+
+	#[test]
+	fn either_side_for_the_incoming_item_gives_the_same_order() {
+		let input = vec![3, 1, 4, 1, 5, 9, 2, 6];
+		let mut shown_as_asked = Sorter::new();
+		shown_as_asked.start_sorting(input.clone()).unwrap();
+		while shown_as_asked.make_choice_with(descending) {}
+
+		// The pair is shown the other way around, so the side picked is flipped back.
+		let mut shown_swapped = Sorter::new();
+		shown_swapped.start_sorting(input).unwrap();
+		while shown_swapped
+			.make_choice_with(|left, right| descending(right, left).map(Choice::flipped))
+		{}
+		assert_eq!(sorted(&shown_as_asked), sorted(&shown_swapped));
+	}
+
+	#[test]
+	fn a_finished_result_has_every_item_ranked() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![2, 7, 1, 8]).unwrap();
+		while sorter.make_choice_with(descending) {}
+		let result = sorter.take_result();
+		assert_eq!(result.items, vec![8, 7, 2, 1]);
+		assert_eq!(result.comparisons, sorter.comparisons());
+		assert!(result.unranked.is_empty());
+	}
+
+	#[test]
+	fn a_result_taken_early_keeps_the_rest_in_their_order() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![1, 2, 3, 4, 5]).unwrap();
+		sorter.make_choice_with(descending);
+		let result = sorter.take_result();
+		assert_eq!(result.items, vec![2, 1]);
+		assert_eq!(result.comparisons, 1);
+		assert_eq!(result.unranked, vec![3, 4, 5]);
+	}
+
+	#[test]
+	fn a_rationale_goes_on_the_choice_it_explains() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![3, 1, 4, 1, 5]).unwrap();
+		sorter.make_choice_with(descending);
+		sorter.make_choice_with(descending);
+		sorter.explain_last_choice("bigger".into());
+		sorter.make_choice_with(descending);
+
+		let rationales: Vec<Option<&str>> = sorter
+			.log()
+			.unwrap()
+			.choices
+			.iter()
+			.map(|decision| decision.rationale.as_deref())
+			.collect();
+		assert_eq!(rationales, [None, Some("bigger"), None]);
+		let replayed = Sorter::from_log(sorter.log().unwrap().clone());
+		assert_eq!(
+			replayed.log().unwrap().choices[1].rationale.as_deref(),
+			Some("bigger")
+		);
+	}
+
+	#[test]
+	fn the_leaderboard_is_the_top_of_what_is_placed() {
+		let comparing = |sorted: Vec<u32>| SortState::Compare {
+			sorted,
+			unsorted: vec![1],
+			lo: 0,
+			hi: 0,
+		};
+		assert!(comparing(vec![]).leaderboard(5).is_empty());
+		assert_eq!(comparing(vec![9, 8, 7]).leaderboard(5), [9, 8, 7]);
+		assert_eq!(
+			comparing(vec![9, 8, 7, 6, 5, 4, 3]).leaderboard(5),
+			[9, 8, 7, 6, 5]
+		);
+		assert!(SortState::Done(vec![9, 8]).leaderboard(5).is_empty());
+	}
+
+	#[test]
+	fn upcoming_items_come_in_the_order_shown() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![5, 3, 8, 1, 9, 2]).unwrap();
+		let upcoming: Vec<u32> = sorter.state.upcoming(3).copied().collect();
+		assert_eq!(upcoming, [8, 1, 9]);
+
+		let mut brought_in = Vec::new();
+		while let Some((&incoming, _)) = sorter.state.current_pair() {
+			if brought_in.last() != Some(&incoming) {
+				brought_in.push(incoming);
+			}
+			sorter.make_choice_with(descending);
+		}
+		assert_eq!(brought_in[1..4], upcoming);
+	}
+
+	#[test]
+	fn a_cloned_sorter_goes_its_own_way() {
+		let mut original = Sorter::default();
+		original.start_sorting(vec![3, 1, 4, 1, 5, 9]).unwrap();
+		original.make_choice_with(descending);
+		let mut copy = original.clone();
+
+		while original.make_choice_with(descending) {}
+		while copy.make_choice_with(|left, right| descending(right, left)) {}
+		assert_eq!(sorted(&original), vec![9, 5, 4, 3, 1, 1]);
+		assert_eq!(sorted(&copy), vec![3, 1, 1, 4, 5, 9]);
+	}
+
+	#[test]
+	fn alternating_ends_still_sorts() {
+		for len in 0..12_u32 {
+			let input: Vec<u32> = (0..len).map(|i| (i * 7) % 11).collect();
+			let mut expected = input.clone();
+			expected.sort_unstable_by(|a, b| b.cmp(a));
+
+			let mut sorter = Sorter::new();
+			sorter.set_alternate(true);
+			if sorter.start_sorting(input).is_err() {
+				continue;
+			}
+			while sorter.make_choice_with(descending) {}
+			assert_eq!(sorted(&sorter), expected);
+			assert_eq!(
+				sorted(&Sorter::from_log(sorter.log().unwrap().clone())),
+				expected
+			);
+		}
+	}
+
+	#[test]
+	fn misuse_is_reported_with_its_error() {
+		let mut sorter = Sorter::new();
+		assert_eq!(
+			sorter.start_sorting(Vec::new()),
+			Err(SorterError::NothingToCompare)
+		);
+		assert_eq!(
+			sorter.make_choice(Choice::Left),
+			Err(SorterError::NotSorting)
+		);
+
+		sorter.start_sorting(vec![1, 2, 3]).unwrap();
+		assert_eq!(sorter.undo_choice(), Err(SorterError::NothingToUndo));
+		assert_eq!(
+			sorter.start_sorting(vec![4, 5]),
+			Err(SorterError::AlreadySorting)
+		);
+
+		while sorter.make_choice_with(descending) {}
+		assert_eq!(
+			sorter.make_choice(Choice::Left),
+			Err(SorterError::NotSorting)
+		);
+	}
+
+	#[test]
+	fn top_k_finds_the_true_top_k_in_fewer_questions() {
+		let input: Vec<u32> = (0..40).map(|i| (i * 17) % 41).collect();
+		let mut expected = input.clone();
+		expected.sort_unstable_by(|a, b| b.cmp(a));
+		let full = comparisons_to_sort(|sorter| sorter.start_sorting(input.clone()).unwrap());
+
+		for k in [1, 3, 10] {
+			let mut sorter = Sorter::new();
+			sorter.start_top_k(input.clone(), k).unwrap();
+			while sorter.make_choice_with(descending) {}
+			let result = sorter.take_result();
+			assert_eq!(result.items, expected[..k]);
+			assert_eq!(result.unranked.len(), input.len() - k);
+			assert!(result.comparisons < full);
+		}
+	}
+
+	#[test]
+	fn finishing_says_how_much_was_ranked() {
+		let mut sorter = Sorter::new();
+		let mut items = vec![7, 8];
+		assert_eq!(sorter.finish_sorting(&mut items), FinishKind::Empty);
+		assert_eq!(items, vec![7, 8]);
+
+		sorter.start_sorting(vec![1, 2, 3, 4]).unwrap();
+		sorter.make_choice_with(descending);
+		assert_eq!(
+			sorter.finish_sorting(&mut items),
+			FinishKind::Partial {
+				placed: 2,
+				remaining: 2,
+			}
+		);
+		assert_eq!(items, vec![2, 1, 3, 4]);
+
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![1, 2, 3, 4]).unwrap();
+		while sorter.make_choice_with(descending) {}
+		assert_eq!(sorter.finish_sorting(&mut items), FinishKind::Complete);
+		assert_eq!(items, vec![4, 3, 2, 1]);
+	}
+
+	#[test]
+	fn finishing_early_keeps_the_rest_in_their_starting_order() {
+		for alternate in [false, true] {
+			let input = vec![5, 3, 8, 1, 9, 2, 7];
+			for answered in 0..6 {
+				let mut sorter = Sorter::new();
+				sorter.set_alternate(alternate);
+				sorter.start_sorting(input.clone()).unwrap();
+				for _ in 0..answered {
+					sorter.make_choice_with(descending);
+				}
+				let placed = sorter.state.placed().to_vec();
+				let rest: Vec<u32> = input
+					.iter()
+					.filter(|item| !placed.contains(item))
+					.copied()
+					.collect();
+				let mut items = Vec::new();
+				sorter.finish_sorting(&mut items);
+				assert_eq!(items[..placed.len()], placed);
+				assert_eq!(
+					items[placed.len()..],
+					rest,
+					"alternate: {alternate}, answered: {answered}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn every_item_tied_with_a_cluster_joins_it() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![31, 5, 34, 33, 37]).unwrap();
+		while sorter.make_choice_with(by_tens) {}
+		let clusters = sorter.clustered_result();
+		assert_eq!(clusters.len(), 2);
+		let mut top = clusters[0].clone();
+		top.sort_unstable();
+		assert_eq!(top, vec![31, 33, 34, 37]);
+		assert_eq!(clusters[1], vec![5]);
+		let ranks: Vec<usize> = sorter.ranked_iter().map(|(rank, ..)| rank).collect();
+		assert_eq!(ranks, [1, 1, 1, 1, 2]);
+	}
+
+	#[test]
+	fn starting_over_a_sort_in_progress_leaves_it_alone() {
+		for strategy in [SortStrategy::BinaryInsertion, SortStrategy::MergeInsertion] {
+			let mut sorter = Sorter::new();
+			sorter.set_strategy(strategy);
+			sorter.start_sorting(vec![3, 1, 4, 1, 5]).unwrap();
+			sorter.make_choice_with(descending);
+			let pair = sorter
+				.state
+				.current_pair()
+				.map(|(&left, &right)| (left, right));
+			let progress = sorter.progress();
+
+			assert_eq!(
+				sorter.start_sorting(vec![9, 8]),
+				Err(SorterError::AlreadySorting)
+			);
+			assert_eq!(
+				sorter.start_top_k(vec![9, 8], 1),
+				Err(SorterError::AlreadySorting)
+			);
+			assert_eq!(
+				sorter.start_incremental(vec![9], vec![8]),
+				Err(SorterError::AlreadySorting)
+			);
+			assert_eq!(
+				sorter
+					.state
+					.current_pair()
+					.map(|(&left, &right)| (left, right)),
+				pair
+			);
+			assert_eq!(sorter.progress(), progress);
+		}
+	}
+
+	#[test]
+	fn the_estimate_bounds_the_questions_asked() {
+		assert_eq!(
+			(1..=6).map(max_comparisons).collect::<Vec<_>>(),
+			[0, 1, 3, 5, 8, 11]
+		);
+		for strategy in [SortStrategy::BinaryInsertion, SortStrategy::MergeInsertion] {
+			for len in 2..30_u32 {
+				let orders = [
+					(0..len).collect::<Vec<_>>(),
+					(0..len).rev().collect(),
+					(0..len).map(|i| (i * 7) % len).collect(),
+				];
+				for input in orders {
+					let asked = comparisons_to_sort(|sorter| {
+						sorter.set_strategy(strategy);
+						sorter.start_sorting(input).unwrap();
+					});
+					assert!(asked <= strategy.max_comparisons(len as usize));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn undo_asks_the_last_question_again() {
+		let mut sorter = Sorter::new();
+		sorter.start_sorting(vec![3, 1, 4, 1, 5, 9, 2, 6]).unwrap();
+		sorter.make_choice_with(descending);
+		let pair = sorter
+			.state
+			.current_pair()
+			.map(|(&left, &right)| (left, right));
+		sorter.make_choice(Choice::Left).unwrap();
+		sorter.undo_choice().unwrap();
+		assert_eq!(
+			sorter
+				.state
+				.current_pair()
+				.map(|(&left, &right)| (left, right)),
+			pair
+		);
+		assert_eq!(sorter.comparisons(), 1);
+
+		while sorter.make_choice_with(descending) {}
+		assert_eq!(sorted(&sorter), vec![9, 6, 5, 4, 3, 2, 1, 1]);
+	}
+
+	#[test]
+	fn progress_counts_up_to_an_estimate_that_only_falls() {
+		let mut sorter = Sorter::new();
+		assert_eq!(sorter.progress(), None);
+		sorter.start_sorting(vec![3, 1, 4, 1, 5, 9, 2, 6]).unwrap();
+		assert_eq!(sorter.progress(), Some((0, max_comparisons(8))));
+		let mut last = max_comparisons(8);
+		while sorter.make_choice_with(descending) {
+			let (done, most) = sorter.progress().unwrap();
+			assert_eq!(done, sorter.comparisons());
+			assert!(most <= last);
+			last = most;
+		}
+		let (done, most) = sorter.progress().unwrap();
+		assert_eq!(done, most);
+	}
+}
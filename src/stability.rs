@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::item::Item;
+
+/// Spearman's rank correlation between two orderings of the same items, from 1 when they agree
+/// completely to -1 when one is the other reversed. Items found in only one list are ignored.
+pub fn order_correlation(a: &[Item], b: &[Item]) -> f32 {
+	let positions: HashMap<u64, usize> =
+		b.iter().enumerate().map(|(i, item)| (item.id, i)).collect();
+	let pairs: Vec<usize> = a
+		.iter()
+		.filter_map(|item| positions.get(&item.id).copied())
+		.collect();
+	let n = pairs.len();
+	if n < 2 {
+		return 1.0;
+	}
+
+	// Re-rank the positions in `b` among the shared items so gaps left by missing ones don't count.
+	let mut order: Vec<usize> = (0..n).collect();
+	order.sort_by_key(|&i| pairs[i]);
+	let mut rank_in_b = vec![0; n];
+	for (rank, i) in order.into_iter().enumerate() {
+		rank_in_b[i] = rank;
+	}
+
+	let squared: f32 = rank_in_b
+		.iter()
+		.enumerate()
+		.map(|(rank_in_a, &rank_in_b)| (rank_in_a as f32 - rank_in_b as f32).powi(2))
+		.sum();
+	let n = n as f32;
+	1.0 - 6.0 * squared / (n * n.mul_add(n, -1.0))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn items(count: usize) -> Vec<Item> {
+		(0..count)
+			.map(|i| Item::new(format!("Item {}", i)))
+			.collect()
+	}
+
+	#[test]
+	fn the_same_order_correlates_fully() {
+		let items = items(5);
+		assert_eq!(order_correlation(&items, &items), 1.0);
+	}
+
+	#[test]
+	fn a_reversed_order_correlates_negatively() {
+		let items = items(5);
+		let reversed: Vec<Item> = items.iter().rev().cloned().collect();
+		assert_eq!(order_correlation(&items, &reversed), -1.0);
+	}
+
+	#[test]
+	fn items_in_only_one_order_are_left_out() {
+		let items = items(5);
+		let mut fewer = items.clone();
+		fewer.remove(2);
+		assert_eq!(order_correlation(&items, &fewer), 1.0);
+	}
+}
@@ -0,0 +1,113 @@
+use std::{fs, io, path::PathBuf};
+
+use directories::ProjectDirs;
+
+use crate::{
+	item::Item,
+	settings::{Settings, WindowGeometry},
+	sorter::Sorter,
+};
+
+const ITEMS_FILE_NAME: &str = "items.json";
+const SETTINGS_FILE_NAME: &str = "settings.json";
+const WINDOW_FILE_NAME: &str = "window.json";
+#[cfg(feature = "resume")]
+const SORT_FILE_NAME: &str = "sort.json";
+
+/// Where files are kept between launches, created if need be.
+fn config_dir() -> io::Result<PathBuf> {
+	let dir = ProjectDirs::from("", "", "priority-sorter")
+		.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?
+		.config_dir()
+		.to_path_buf();
+	fs::create_dir_all(&dir)?;
+	Ok(dir)
+}
+
+/// The list saved by the last run, or an empty one if there is none. A file that isn't a list
+/// is moved aside first, so saving the new list doesn't overwrite the only copy of the old one.
+pub fn load_items() -> io::Result<Vec<Item>> {
+	let path = config_dir()?.join(ITEMS_FILE_NAME);
+	let json = match fs::read_to_string(&path) {
+		Ok(json) => json,
+		Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(error) => return Err(error),
+	};
+	serde_json::from_str(&json).or_else(|error| {
+		let backup = path.with_extension("json.bak");
+		fs::rename(&path, &backup)?;
+		Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("it was moved to {} ({})", backup.display(), error),
+		))
+	})
+}
+
+pub fn save_items(items: &[Item]) -> io::Result<()> {
+	fs::write(
+		config_dir()?.join(ITEMS_FILE_NAME),
+		serde_json::to_string_pretty(items)?,
+	)
+}
+
+/// The settings from the last run, or the defaults if there are none or they can't be read.
+pub fn load_settings() -> Settings {
+	config_dir()
+		.and_then(|dir| fs::read_to_string(dir.join(SETTINGS_FILE_NAME)))
+		.ok()
+		.and_then(|json| serde_json::from_str(&json).ok())
+		.unwrap_or_default()
+}
+
+pub fn save_settings(settings: &Settings) -> io::Result<()> {
+	fs::write(
+		config_dir()?.join(SETTINGS_FILE_NAME),
+		serde_json::to_string_pretty(settings)?,
+	)
+}
+
+/// The window's size and position from the last run, with anything unusable back at the
+/// defaults.
+pub fn load_window() -> WindowGeometry {
+	config_dir()
+		.and_then(|dir| fs::read_to_string(dir.join(WINDOW_FILE_NAME)))
+		.ok()
+		.and_then(|json| serde_json::from_str::<WindowGeometry>(&json).ok())
+		.unwrap_or_default()
+		.validated()
+}
+
+pub fn save_window(window: WindowGeometry) -> io::Result<()> {
+	fs::write(
+		config_dir()?.join(WINDOW_FILE_NAME),
+		serde_json::to_string(&window)?,
+	)
+}
+
+/// Keeps a sort in progress for the next launch.
+#[cfg(feature = "resume")]
+pub fn save_sort(sorter: &Sorter<Item>) -> io::Result<()> {
+	sorter.save_state(&config_dir()?.join(SORT_FILE_NAME))
+}
+
+#[cfg(not(feature = "resume"))]
+pub fn save_sort(_sorter: &Sorter<Item>) -> io::Result<()> {
+	Err(io::Error::new(
+		io::ErrorKind::Unsupported,
+		"built without the resume feature",
+	))
+}
+
+/// The sort kept by the last run, if any. It is only offered once.
+#[cfg(feature = "resume")]
+pub fn take_sort() -> Option<Sorter<Item>> {
+	let path = config_dir().ok()?.join(SORT_FILE_NAME);
+	let sorter = Sorter::load_state(&path).ok();
+	let _ = fs::remove_file(path);
+	sorter
+}
+
+#[cfg(not(feature = "resume"))]
+pub const fn take_sort() -> Option<Sorter<Item>> {
+	None
+}
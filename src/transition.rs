@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+const DURATION: Duration = Duration::from_millis(250);
+/// How far, in pixels, a new pair starts from where it settles.
+const DISTANCE: f32 = 80.0;
+
+/// A new pair of items sliding into place, driven by window frames.
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+	started: Instant,
+	now: Instant,
+}
+
+impl Transition {
+	pub const fn new(now: Instant) -> Self {
+		Self { started: now, now }
+	}
+
+	pub const fn tick(&mut self, now: Instant) {
+		self.now = now;
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.now.duration_since(self.started) >= DURATION
+	}
+
+	/// How far the pair still is from its resting place, easing out as it arrives.
+	pub fn offset(&self) -> f32 {
+		let progress =
+			(self.now.duration_since(self.started).as_secs_f32() / DURATION.as_secs_f32()).min(1.0);
+		DISTANCE * (1.0 - progress).powi(2)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn the_pair_slides_in_and_stops() {
+		let start = Instant::now();
+		let mut transition = Transition::new(start);
+		assert_eq!(transition.offset(), DISTANCE);
+		assert!(!transition.is_finished());
+
+		transition.tick(start + DURATION / 2);
+		assert!(transition.offset() > 0.0 && transition.offset() < DISTANCE / 2.0);
+
+		transition.tick(start + DURATION);
+		assert!(transition.is_finished());
+		assert_eq!(transition.offset(), 0.0);
+	}
+}
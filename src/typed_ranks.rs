@@ -0,0 +1,64 @@
+/// A new order for a list built from ranks typed next to its items.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TypedOrder {
+	/// Indices into the list, in their new order.
+	pub order: Vec<usize>,
+	/// Ranks typed for more than one item. Those items keep their relative order.
+	pub duplicates: Vec<usize>,
+	/// Indices of items whose typed rank is not a positive number. They are treated as untyped.
+	pub invalid: Vec<usize>,
+}
+
+/// Orders the list by the rank typed for each item, one entry per item. An item left blank keeps
+/// its current position as its rank, gaps in the numbering are fine, and ties keep list order.
+pub fn typed_order(typed: &[&str]) -> TypedOrder {
+	let mut result = TypedOrder::default();
+	let mut keys = Vec::with_capacity(typed.len());
+	for (i, input) in typed.iter().map(|input| input.trim()).enumerate() {
+		let rank = match input.parse::<usize>() {
+			Ok(rank) if rank > 0 => Some(rank),
+			_ if input.is_empty() => None,
+			_ => {
+				result.invalid.push(i);
+				None
+			},
+		};
+		if let Some(rank) = rank {
+			let taken = keys.iter().any(|&(key, typed)| typed && key == rank);
+			if taken && !result.duplicates.contains(&rank) {
+				result.duplicates.push(rank);
+			}
+		}
+		keys.push((rank.unwrap_or(i + 1), rank.is_some()));
+	}
+
+	result.order = (0..typed.len()).collect();
+	result.order.sort_by_key(|&i| keys[i].0);
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn gaps_in_the_numbering_are_fine() {
+		let typed = typed_order(&["10", "", "1"]);
+		assert_eq!(typed.order, [2, 1, 0]);
+		assert!(typed.duplicates.is_empty() && typed.invalid.is_empty());
+	}
+
+	#[test]
+	fn a_rank_typed_twice_keeps_list_order() {
+		let typed = typed_order(&["2", "1", "2"]);
+		assert_eq!(typed.order, [1, 0, 2]);
+		assert_eq!(typed.duplicates, [2]);
+	}
+
+	#[test]
+	fn ranks_that_arent_positive_numbers_are_reported() {
+		let typed = typed_order(&["x", "0", "1"]);
+		assert_eq!(typed.order, [0, 2, 1]);
+		assert_eq!(typed.invalid, [0, 1]);
+	}
+}